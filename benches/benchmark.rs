@@ -1,9 +1,9 @@
 #[macro_use]
 extern crate criterion;
-extern crate space_efficient_quantile;
+extern crate fast_quantiles;
 
 use criterion::{BenchmarkId, Criterion};
-use space_efficient_quantile::*;
+use fast_quantiles::*;
 
 pub fn quantile_generator_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("quantile_generator");
@@ -30,21 +30,9 @@ pub fn summary_benchmark(c: &mut Criterion) {
     let nums: Vec<usize> = vec![100, 1_000, 10_000, 100_000];
     let epsilon = 0.001;
     for num in nums {
-        if num <= 1_000 {
-            group.bench_with_input(BenchmarkId::new("GK", num), &num, |b, &num| {
-                b.iter(|| {
-                    let mut sum = gk::Summary::new(epsilon);
-                    for value in quantile_generator::RandomGenerator::new(0.5, 17., num, 17) {
-                        sum.insert_one(value);
-                    }
-                    assert_ne!(sum.query(0.5), None);
-                })
-            });
-        }
-
-        group.bench_with_input(BenchmarkId::new("Modified GK", num), &num, |b, &num| {
+        group.bench_with_input(BenchmarkId::new("Summary", num), &num, |b, &num| {
             b.iter(|| {
-                let mut sum = modified_gk::Summary::new(epsilon);
+                let mut sum = Summary::new(epsilon);
                 for value in quantile_generator::RandomGenerator::new(0.5, 17., num, 17) {
                     sum.insert_one(value);
                 }
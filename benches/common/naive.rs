@@ -0,0 +1,15 @@
+//! The naive algorithm: it stores every value and sorts them to answer a query. It is exact, so
+//! it is the baseline the other algorithms are checked against
+
+use fast_quantiles::quantile_to_rank;
+
+/// Sort the values and return the one at the desired quantile
+/// Return None if and only if there are no values
+pub fn query<T: Ord>(values: &mut [T], quantile: f64) -> Option<&T> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    let rank = quantile_to_rank(quantile, values.len() as u64);
+    Some(&values[(rank - 1) as usize])
+}
@@ -1,99 +1,103 @@
-use crate::quantile_generator::{OrderedF64, RandomGenerator};
-use std::env;
-use std::thread;
+//! Measure the insert throughput and the query latency of each algorithm, for different input
+//! sizes and maximum errors
+//!
+//! The `gk` and `modified_gk` algorithms are gone from the crate, so `fast` (the crate's
+//! `Summary`) is compared against the `naive` exact baseline.
 
-/// Calculate the median of a big sequence of values, used for benchmarking
-/// Takes in the environment values:
-/// - ALGORITHM: string, one of 'NAIVE', 'GK', 'MODIFIED_GK'
-/// - THREADS: uint
-/// - VALUES: uint
-/// - EPSILON: float
-fn main() {
-    let values: u64 = env::var("VALUES").unwrap().parse().unwrap();
-    let threads: u64 = env::var("THREADS").unwrap().parse().unwrap();
-    match env::var("ALGORITHM").unwrap().as_str() {
-        "NAIVE" => run_algorithm::<naive::Algorithm>(values, threads),
-        "GK" => run_algorithm::<gk::Algorithm>(values, threads),
-        "MODIFIED_GK" => run_algorithm::<modified_gk::Algorithm>(values, threads),
-        _ => panic!("Invalid choice of algorithm"),
-    }
-}
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fast_quantiles::quantile_generator::{OrderedF64, RandomGenerator};
+
+#[path = "common/naive.rs"]
+mod naive_query;
 
-trait Summary: Send {
-    fn new() -> Self;
+const NUMS: [usize; 3] = [1_000, 10_000, 100_000];
+const EPSILONS: [f64; 2] = [0.01, 0.001];
+
+trait Summary {
+    fn new(epsilon: f64) -> Self;
     fn insert_one(&mut self, value: OrderedF64);
-    fn merge(&mut self, other: Self);
-    fn query_with_error(&mut self, quantile: f64) -> Option<(&OrderedF64, f64)>;
+    fn query(&mut self, quantile: f64) -> Option<&OrderedF64>;
 }
 
-fn run_algorithm<S: Summary + 'static>(values: u64, threads: u64) {
-    let num_values_per_thread = values / threads;
+fn values(num: usize) -> Vec<OrderedF64> {
+    RandomGenerator::new(0.5, 17., num, 17)
+        .map(OrderedF64::from)
+        .collect()
+}
 
-    let thread_handles = (0..threads)
-        .map(|seed| {
-            thread::spawn(move || {
-                let mut summary = S::new();
-                for value in RandomGenerator::new(0.5, 17., num_values_per_thread as usize, seed) {
-                    summary.insert_one(value);
-                }
-                summary
-            })
-        })
-        .collect::<Vec<_>>();
+fn fill<S: Summary>(epsilon: f64, values: &[OrderedF64]) -> S {
+    let mut summary = S::new(epsilon);
+    for &value in values {
+        summary.insert_one(black_box(value));
+    }
+    summary
+}
+
+fn bench_algorithm<S: Summary>(c: &mut Criterion, name: &str) {
+    let mut insert_group = c.benchmark_group("insert");
+    for &num in &NUMS {
+        let values = values(num);
+        insert_group.throughput(Throughput::Elements(num as u64));
+        for &epsilon in &EPSILONS {
+            let id = BenchmarkId::new(format!("{}/{}", name, epsilon), num);
+            insert_group.bench_with_input(id, &values, |b, values| {
+                b.iter(|| fill::<S>(epsilon, values))
+            });
+        }
+    }
+    insert_group.finish();
 
-    let summaries = thread_handles
-        .into_iter()
-        .map(|t| t.join().unwrap())
-        .collect::<Vec<_>>();
+    let mut query_group = c.benchmark_group("query");
+    for &num in &NUMS {
+        let values = values(num);
+        for &epsilon in &EPSILONS {
+            let mut summary = fill::<S>(epsilon, &values);
+            let id = BenchmarkId::new(format!("{}/{}", name, epsilon), num);
+            query_group.bench_function(id, |b| {
+                b.iter(|| black_box(summary.query(black_box(0.5)).copied()))
+            });
+        }
+    }
+    query_group.finish();
+}
 
-    let mut summary = summaries
-        .into_iter()
-        .fold(None, |a, b| match a {
-            None => Some(b),
-            Some(mut a) => {
-                a.merge(b);
-                Some(a)
-            }
-        })
-        .unwrap();
+fn naive_benchmark(c: &mut Criterion) {
+    bench_algorithm::<naive::Algorithm>(c, "naive");
+}
 
-    println!("{:?}", summary.query_with_error(0.5));
+fn fast_benchmark(c: &mut Criterion) {
+    bench_algorithm::<fast::Algorithm>(c, "fast");
 }
 
+criterion_group!(benches, naive_benchmark, fast_benchmark);
+criterion_main!(benches);
+
 mod naive {
     use super::{OrderedF64, Summary};
-    use space_efficient_quantile::quantile_to_rank;
     pub struct Algorithm {
         values: Vec<OrderedF64>,
     }
     impl Summary for Algorithm {
-        fn new() -> Self {
+        fn new(_epsilon: f64) -> Self {
             Algorithm { values: Vec::new() }
         }
         fn insert_one(&mut self, value: OrderedF64) {
             self.values.push(value);
         }
-        fn merge(&mut self, mut other: Self) {
-            self.values.append(&mut other.values);
-        }
-        fn query_with_error(&mut self, quantile: f64) -> Option<(&OrderedF64, f64)> {
-            self.values.sort();
-            let rank = quantile_to_rank(quantile, self.values.len() as u64);
-            Some((&self.values[rank as usize], 0.))
+        fn query(&mut self, quantile: f64) -> Option<&OrderedF64> {
+            super::naive_query::query(&mut self.values, quantile)
         }
     }
 }
 
-mod gk {
+mod fast {
     use super::{OrderedF64, Summary};
-    use space_efficient_quantile::gk::Summary as InternalSummary;
-    use std::env;
+    use fast_quantiles::Summary as InternalSummary;
     pub struct Algorithm {
         summary: InternalSummary<OrderedF64>,
     }
     impl Summary for Algorithm {
-        fn new() -> Self {
-            let epsilon: f64 = env::var("EPSILON").unwrap().parse().unwrap();
+        fn new(epsilon: f64) -> Self {
             Algorithm {
                 summary: InternalSummary::new(epsilon),
             }
@@ -101,37 +105,8 @@ mod gk {
         fn insert_one(&mut self, value: OrderedF64) {
             self.summary.insert_one(value);
         }
-        fn merge(&mut self, other: Self) {
-            self.summary.merge(other.summary);
-        }
-        fn query_with_error(&mut self, quantile: f64) -> Option<(&OrderedF64, f64)> {
-            self.summary.query_with_error(quantile)
-        }
-    }
-}
-
-mod modified_gk {
-    use super::{OrderedF64, Summary};
-    use space_efficient_quantile::modified_gk::Summary as InternalSummary;
-    use std::env;
-    pub struct Algorithm {
-        summary: InternalSummary<OrderedF64>,
-    }
-    impl Summary for Algorithm {
-        fn new() -> Self {
-            let epsilon: f64 = env::var("EPSILON").unwrap().parse().unwrap();
-            Algorithm {
-                summary: InternalSummary::new(epsilon),
-            }
-        }
-        fn insert_one(&mut self, value: OrderedF64) {
-            self.summary.insert_one(value);
-        }
-        fn merge(&mut self, other: Self) {
-            self.summary.merge(other.summary);
-        }
-        fn query_with_error(&mut self, quantile: f64) -> Option<(&OrderedF64, f64)> {
-            self.summary.query_with_error(quantile)
+        fn query(&mut self, quantile: f64) -> Option<&OrderedF64> {
+            self.summary.query(quantile)
         }
     }
 }
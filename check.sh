@@ -4,4 +4,6 @@ echo "=== cargo clippy ==="
 # This is required because clippy does not rebuild by default
 # See https://github.com/rust-lang/rust-clippy/issues/2604
 touch src/lib.rs
-cargo clippy --all-targets --all-features -- -D clippy::all
\ No newline at end of file
+cargo clippy --all-targets --all-features -- -D clippy::all
+echo "=== cargo test (no_std) ==="
+cargo test --no-default-features
@@ -1,48 +1,51 @@
 use super::samples_compressor::SamplesCompressor;
-use super::samples_tree::Sample;
+use super::samples_tree::Checkpoint;
+use core::mem;
 
-/// Keep metadata about an incoming iterator of sorted samples
-pub struct IncomingMergeState<T: Ord, I: Iterator<Item = Sample<T>>> {
+/// Keep metadata about an incoming iterator of sorted checkpoints
+pub struct IncomingMergeState<S, I: Iterator<Item = Checkpoint<S>>> {
     iterator: I,
-    next_sample: Option<Sample<T>>,
+    next_checkpoint: Option<Checkpoint<S>>,
     has_started: bool,
 }
 
-impl<T: Ord, I: Iterator<Item = Sample<T>>> IncomingMergeState<T, I> {
+impl<S: Ord, I: Iterator<Item = Checkpoint<S>>> IncomingMergeState<S, I> {
     /// Wrap an iterator
     pub fn new(mut iter: I) -> Self {
         IncomingMergeState {
-            next_sample: iter.next(),
+            next_checkpoint: iter.next(),
             iterator: iter,
             has_started: false,
         }
     }
 
-    /// Get a reference to next sample
-    pub fn peek(&self) -> Option<&Sample<T>> {
-        self.next_sample.as_ref()
+    /// Get a reference to next checkpoint
+    pub fn peek(&self) -> Option<&Checkpoint<S>> {
+        self.next_checkpoint.as_ref()
     }
 
-    /// Return the next sample and prepare the next one
-    pub fn pop_front(&mut self) -> Sample<T> {
+    /// Return the next checkpoint and prepare the next one
+    pub fn pop_front(&mut self) -> Checkpoint<S> {
         self.has_started = true;
-        std::mem::replace(&mut self.next_sample, self.iterator.next()).unwrap()
+        mem::replace(&mut self.next_checkpoint, self.iterator.next()).unwrap()
     }
 
-    /// Calculate by how much a sample's delta from another incoming iterator should be increased
-    pub fn aditional_delta(&self) -> u64 {
-        match &self.next_sample {
-            Some(real_sample) if self.has_started => real_sample.g + real_sample.delta - 1,
+    /// Calculate by how much the `max_gap` of a checkpoint from another incoming iterator should
+    /// be increased, since any of the samples between the last popped checkpoint and the next one
+    /// could be before it
+    pub fn additional_gap(&self) -> u64 {
+        match &self.next_checkpoint {
+            Some(checkpoint) if self.has_started => checkpoint.max_gap() - 1,
             _ => 0,
         }
     }
 
-    /// Exaust the iterator, moving values to the given compressor
-    pub fn push_remaining_to(self, compressor: &mut SamplesCompressor<T>) {
-        if let Some(sample) = self.next_sample {
-            compressor.push(sample);
-            for sample in self.iterator {
-                compressor.push(sample);
+    /// Exhaust the iterator, moving checkpoints to the given compressor
+    pub fn push_remaining_to<const CAP: usize>(self, compressor: &mut SamplesCompressor<S, CAP>) {
+        if let Some(checkpoint) = self.next_checkpoint {
+            compressor.push(checkpoint);
+            for checkpoint in self.iterator {
+                compressor.push(checkpoint);
             }
         }
     }
@@ -51,63 +54,50 @@ impl<T: Ord, I: Iterator<Item = Sample<T>>> IncomingMergeState<T, I> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn pop_all() {
-        let sample1 = Sample {
-            value: 3,
-            g: 1,
-            delta: 2,
-        };
-        let sample2 = Sample {
-            value: 14,
-            g: 3,
-            delta: 4,
-        };
-        let sample3 = Sample {
-            value: 15,
-            g: 5,
-            delta: 6,
-        };
-        let samples = vec![sample1, sample2, sample3].into_iter();
+        let checkpoints = vec![
+            Checkpoint::with_gaps(3, 1, 3),
+            Checkpoint::with_gaps(14, 3, 7),
+            Checkpoint::with_gaps(15, 5, 11),
+        ];
 
-        let mut incoming = IncomingMergeState::new(samples);
+        let mut incoming = IncomingMergeState::new(checkpoints.into_iter());
 
-        assert_eq!(incoming.peek(), Some(&sample1));
-        assert_eq!(incoming.aditional_delta(), 0);
-        assert_eq!(incoming.pop_front(), sample1);
+        assert_eq!(incoming.peek().map(Checkpoint::sample), Some(&3));
+        assert_eq!(incoming.additional_gap(), 0);
+        assert_eq!(*incoming.pop_front().sample(), 3);
 
-        assert_eq!(incoming.peek(), Some(&sample2));
-        assert_eq!(incoming.aditional_delta(), 6);
-        assert_eq!(incoming.pop_front(), sample2);
+        assert_eq!(incoming.peek().map(Checkpoint::sample), Some(&14));
+        assert_eq!(incoming.additional_gap(), 6);
+        assert_eq!(*incoming.pop_front().sample(), 14);
 
-        assert_eq!(incoming.peek(), Some(&sample3));
-        assert_eq!(incoming.aditional_delta(), 10);
-        assert_eq!(incoming.pop_front(), sample3);
+        assert_eq!(incoming.peek().map(Checkpoint::sample), Some(&15));
+        assert_eq!(incoming.additional_gap(), 10);
+        assert_eq!(*incoming.pop_front().sample(), 15);
 
-        assert_eq!(incoming.peek(), None);
-        assert_eq!(incoming.aditional_delta(), 0);
+        assert_eq!(incoming.peek().map(Checkpoint::sample), None);
+        assert_eq!(incoming.additional_gap(), 0);
 
-        let mut empty = SamplesCompressor::new(1);
+        let mut empty = SamplesCompressor::<_, 4>::new(1);
         incoming.push_remaining_to(&mut empty);
         assert_eq!(empty.into_samples_tree().len(), 0);
     }
 
     #[test]
     fn pop_none() {
-        let samples = vec![3, 14, 15].into_iter().map(|value| Sample {
-            value,
-            g: 1,
-            delta: 0,
-        });
-        let incoming = IncomingMergeState::new(samples);
-        let mut empty = SamplesCompressor::new(1);
+        let checkpoints = vec![3, 14, 15].into_iter().map(Checkpoint::new_exact);
+        let incoming = IncomingMergeState::new(checkpoints);
+        let mut empty = SamplesCompressor::<_, 4>::new(1);
         incoming.push_remaining_to(&mut empty);
         assert_eq!(
             empty
                 .into_samples_tree()
                 .iter()
-                .map(|sample| sample.value)
+                .map(|checkpoint| *checkpoint.sample())
                 .collect::<Vec<i32>>(),
             vec![3, 14, 15]
         );
@@ -0,0 +1,113 @@
+use super::summary::SummaryWithCapacity;
+
+/// The common interface of the structures that summarize a stream of samples to answer quantile
+/// queries, so that generic code can be written over any of them.
+///
+/// How a summary is created differs between implementations, so it is not part of this trait.
+pub trait QuantileSummary<S: Ord> {
+    /// Insert a single new sample into the summary
+    fn insert_one(&mut self, sample: S);
+
+    /// Merge another summary into this one
+    fn merge(&mut self, other: Self)
+    where
+        Self: Sized;
+
+    /// Query for a desired quantile
+    /// Return None if and only if the summary is empty
+    fn query(&self, quantile: f64) -> Option<&S>;
+
+    /// Query for a desired quantile, also returning the greatest possible error of the answer,
+    /// as a fraction of the number of inserted samples.
+    /// Return None if and only if the summary is empty
+    fn query_with_error(&self, quantile: f64) -> Option<(&S, f64)>;
+
+    /// Get the number of inserted samples
+    fn len(&self) -> u64;
+
+    /// Return whether no sample was inserted yet
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<S: Ord, const CAP: usize> QuantileSummary<S> for SummaryWithCapacity<S, CAP> {
+    fn insert_one(&mut self, sample: S) {
+        SummaryWithCapacity::insert_one(self, sample)
+    }
+
+    fn merge(&mut self, other: Self) {
+        SummaryWithCapacity::merge(self, other)
+    }
+
+    fn query(&self, quantile: f64) -> Option<&S> {
+        SummaryWithCapacity::query(self, quantile)
+    }
+
+    fn query_with_error(&self, quantile: f64) -> Option<(&S, f64)> {
+        SummaryWithCapacity::query_with_error(self, quantile)
+    }
+
+    fn len(&self) -> u64 {
+        SummaryWithCapacity::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{quantile_to_rank, rank_to_quantile, Summary};
+
+    /// Insert a permutation of `0..num`, so that the rank of each sample is `sample + 1`, split
+    /// across both summaries and merge them. Then check every answer against the summary's own
+    /// error bound and `epsilon`
+    fn check_accuracy<Q: QuantileSummary<u64>>(
+        mut summary: Q,
+        mut other: Q,
+        epsilon: f64,
+        num: u64,
+    ) {
+        assert!(summary.is_empty());
+        assert_eq!(summary.query(0.5), None);
+        assert_eq!(summary.query_with_error(0.5), None);
+
+        // 7919 is a prime that does not divide `num`, so this visits every value in a scrambled order
+        for i in 0..num {
+            let sample = i * 7919 % num;
+            if i % 3 == 0 {
+                other.insert_one(sample);
+            } else {
+                summary.insert_one(sample);
+            }
+        }
+        summary.merge(other);
+        assert_eq!(summary.len(), num);
+
+        for rank in 1..=num {
+            let quantile = rank_to_quantile(rank, num);
+            // The round trip is not exact in floating point
+            let rank = quantile_to_rank(quantile, num);
+            let (&sample, error) = summary.query_with_error(quantile).unwrap();
+            assert_eq!(summary.query(quantile), Some(&sample));
+
+            let real_error = ((sample + 1) as f64 - rank as f64).abs() / num as f64;
+            assert!(real_error <= error, "rank={}, sample={}", rank, sample);
+            assert!(error <= epsilon, "rank={}, error={}", rank, error);
+        }
+    }
+
+    #[test]
+    fn accuracy() {
+        for &epsilon in &[0.1, 0.01] {
+            for &num in &[1, 10, 1000, 10_000] {
+                check_accuracy(Summary::new(epsilon), Summary::new(epsilon), epsilon, num);
+                check_accuracy(
+                    SummaryWithCapacity::<_, 4>::new(epsilon),
+                    SummaryWithCapacity::new(epsilon),
+                    epsilon,
+                    num,
+                );
+            }
+        }
+    }
+}
@@ -1,113 +1,92 @@
-use super::samples_tree::{Sample, SamplesTree};
+use super::samples_tree::{Checkpoint, SamplesTree};
 
-/// Helper structure that compress samples as they are given, in sorted order
-pub struct SamplesCompressor<T: Ord> {
-    max_g_delta: u64,
-    compressed_samples: SamplesTree<T>,
-    block_tail: Option<Sample<T>>,
+/// Helper structure that compress checkpoints as they are given, in sorted order
+pub struct SamplesCompressor<S, const CAP: usize> {
+    maximal_gap: u64,
+    compressed_checkpoints: SamplesTree<S, CAP>,
+    block_tail: Option<Checkpoint<S>>,
 }
 
-impl<T: Ord> SamplesCompressor<T> {
-    pub fn new(max_g_delta: u64) -> Self {
+impl<S: Ord, const CAP: usize> SamplesCompressor<S, CAP> {
+    pub fn new(maximal_gap: u64) -> Self {
         SamplesCompressor {
-            max_g_delta,
-            compressed_samples: SamplesTree::new(),
+            maximal_gap,
+            compressed_checkpoints: SamplesTree::new(),
             block_tail: None,
         }
     }
 
-    pub fn push(&mut self, mut sample: Sample<T>) {
-        if let Some(tail_sample) = self.block_tail.take() {
-            if tail_sample.g + sample.g + sample.delta <= self.max_g_delta {
-                // Add new sample to the current compression block
-                sample.g += tail_sample.g;
+    pub fn push(&mut self, mut checkpoint: Checkpoint<S>) {
+        if let Some(tail_checkpoint) = self.block_tail.take() {
+            if checkpoint.can_absorb(&tail_checkpoint, self.maximal_gap) {
+                // Add new checkpoint to the current compression block
+                checkpoint.absorb(tail_checkpoint);
             } else {
                 // Commit previous block and start new
-                self.compressed_samples.insert_max_sample(tail_sample);
+                self.compressed_checkpoints
+                    .insert_max_checkpoint(tail_checkpoint);
             }
-            self.block_tail = Some(sample);
-        } else if self.compressed_samples.len() == 0 {
+            self.block_tail = Some(checkpoint);
+        } else if self.compressed_checkpoints.is_empty() {
             // Commit minimum
-            self.compressed_samples.insert_max_sample(sample);
+            self.compressed_checkpoints
+                .insert_max_checkpoint(checkpoint);
         } else {
             // Start first block
-            self.block_tail = Some(sample);
+            self.block_tail = Some(checkpoint);
         }
     }
 
-    pub fn into_samples_tree(mut self) -> SamplesTree<T> {
-        if let Some(tail_sample) = self.block_tail {
+    pub fn into_samples_tree(mut self) -> SamplesTree<S, CAP> {
+        if let Some(tail_checkpoint) = self.block_tail {
             // Commit last block
-            self.compressed_samples.insert_max_sample(tail_sample);
+            self.compressed_checkpoints
+                .insert_max_checkpoint(tail_checkpoint);
         }
-        self.compressed_samples
+        self.compressed_checkpoints
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn spec<const CAP: usize>(tree: SamplesTree<i32, CAP>) -> Vec<(i32, u64, u64)> {
+        tree.into_iter()
+            .map(|checkpoint| {
+                (
+                    *checkpoint.sample(),
+                    checkpoint.min_gap(),
+                    checkpoint.max_gap(),
+                )
+            })
+            .collect()
+    }
 
     #[test]
     fn compress() {
-        let samples = (0..9).map(|value| Sample {
-            value,
-            g: 1,
-            delta: 2,
-        });
-
-        let mut compressor = SamplesCompressor::new(5);
-        for sample in samples {
-            compressor.push(sample);
+        let mut compressor = SamplesCompressor::<_, 4>::new(5);
+        for sample in 0..9 {
+            compressor.push(Checkpoint::with_gaps(sample, 1, 3));
         }
 
         assert_eq!(
-            compressor
-                .into_samples_tree()
-                .iter()
-                .cloned()
-                .collect::<Vec<_>>(),
-            vec![
-                Sample {
-                    value: 0,
-                    g: 1,
-                    delta: 2
-                },
-                Sample {
-                    value: 3,
-                    g: 3,
-                    delta: 2
-                },
-                Sample {
-                    value: 6,
-                    g: 3,
-                    delta: 2
-                },
-                Sample {
-                    value: 8,
-                    g: 2,
-                    delta: 2
-                }
-            ]
+            spec(compressor.into_samples_tree()),
+            vec![(0, 1, 3), (3, 3, 5), (6, 3, 5), (8, 2, 4)]
         );
     }
 
     #[test]
     fn no_compression() {
         for len in 0..3 {
-            let mut compressor = SamplesCompressor::<i32>::new(1);
-            let samples = (0..len).map(Sample::exact).collect::<Vec<Sample<i32>>>();
-            for &sample in &samples {
-                compressor.push(sample);
+            let mut compressor = SamplesCompressor::<_, 4>::new(1);
+            for sample in 0..len {
+                compressor.push(Checkpoint::new_exact(sample));
             }
-            assert_eq!(
-                compressor
-                    .into_samples_tree()
-                    .iter()
-                    .cloned()
-                    .collect::<Vec<_>>(),
-                samples
-            );
+            let expected: Vec<_> = (0..len).map(|sample| (sample, 1, 1)).collect();
+            assert_eq!(spec(compressor.into_samples_tree()), expected);
         }
     }
 }
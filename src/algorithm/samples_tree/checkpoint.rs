@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 
 /// Represent the samples that were captured as checkpoints
 #[derive(Debug, Copy, Clone)]
@@ -33,14 +33,24 @@ impl<S> Checkpoint<S> {
         }
     }
 
-    /// Return if the checkpoint is a exact sample
-    pub fn is_exact(&self) -> bool {
-        self.max_gap == 1
+    /// Return the captured sample
+    pub fn sample(&self) -> &S {
+        &self.sample
+    }
+
+    /// Return the least number of samples between the preceding checkpoint and this one
+    pub fn min_gap(&self) -> u64 {
+        self.min_gap
+    }
+
+    /// Return the greatest number of samples between the preceding checkpoint and this one
+    pub fn max_gap(&self) -> u64 {
+        self.max_gap
     }
 
     /// Return if this checkpoint can grow to represent one more sample
     pub fn can_grow(&self, maximal_gap: u64) -> bool {
-        self.max_gap + 1 <= maximal_gap
+        self.max_gap < maximal_gap
     }
 
     /// Record a new sample in the preceding checkpoint
@@ -53,6 +63,33 @@ impl<S> Checkpoint<S> {
     pub fn swap_sample(&mut self, new_sample: S) {
         self.sample = new_sample;
     }
+
+    /// Return if this checkpoint can absorb the preceding one, that is, represent its samples too
+    pub fn can_absorb(&self, preceding: &Self, maximal_gap: u64) -> bool {
+        preceding.min_gap + self.max_gap <= maximal_gap
+    }
+
+    /// Forget the preceding checkpoint, representing its samples in this one instead
+    pub fn absorb(&mut self, preceding: Self) {
+        self.min_gap += preceding.min_gap;
+        self.max_gap += preceding.min_gap;
+    }
+
+    /// Account for `uncertainty` more possible samples between the preceding checkpoint and this
+    /// one
+    pub fn widen(&mut self, uncertainty: u64) {
+        self.max_gap += uncertainty;
+    }
+
+    /// Return a new checkpoint with the given gaps
+    #[cfg(test)]
+    pub fn with_gaps(sample: S, min_gap: u64, max_gap: u64) -> Self {
+        Checkpoint {
+            sample,
+            min_gap,
+            max_gap,
+        }
+    }
 }
 
 // Delegate PartialEq, PartialOrd, Eq and Ord to the field `sample`
@@ -85,6 +122,6 @@ impl<S: PartialEq> PartialEq<S> for Checkpoint<S> {
 
 impl<S: PartialOrd> PartialOrd<S> for Checkpoint<S> {
     fn partial_cmp(&self, other: &S) -> Option<Ordering> {
-        self.sample.partial_cmp(&other)
+        self.sample.partial_cmp(other)
     }
 }
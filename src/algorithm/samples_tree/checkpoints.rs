@@ -1,25 +1,21 @@
-use super::{Checkpoint, NODE_CAPACITY};
+use super::{is_valid_capacity, Checkpoint};
 use crate::algorithm::samples_tree::node::InsertResult;
 use arrayvec::ArrayVec;
-use std::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut};
 
 /// A list of checkpoints using a static-sized array as storage.
 ///
 /// The main advantage over a normal `Vec` is that there is one lesser heap allocation.
 #[derive(Debug)]
-pub struct Checkpoints<S>(ArrayVec<[Checkpoint<S>; NODE_CAPACITY]>);
+pub struct Checkpoints<S, const CAP: usize>(ArrayVec<Checkpoint<S>, CAP>);
 
-#[derive(Debug)]
-pub enum LeafInsertPos<'a, S> {
-    /// The given sample is larger than all values in the whole tree
-    GlobalMax,
-    /// Other cases: carry the index and the first checkpoint that is larger than the given sample
-    Other(usize, &'a mut Checkpoint<S>),
-}
+impl<S, const CAP: usize> Checkpoints<S, CAP> {
+    // Refuse to build with capacities that would silently break node splitting
+    const VALID_CAPACITY: () = assert!(is_valid_capacity(CAP), "CAP must be even and positive");
 
-impl<S> Checkpoints<S> {
     /// Create a new empty list of checkpoints
     pub fn new() -> Self {
+        let () = Self::VALID_CAPACITY;
         Self(ArrayVec::new())
     }
 
@@ -67,7 +63,7 @@ impl<S> Checkpoints<S> {
     }
 }
 
-impl<S: Ord> Checkpoints<S> {
+impl<S: Ord, const CAP: usize> Checkpoints<S, CAP> {
     /// Return the insertion position for this sample in a leaf node
     pub fn find_insertion_pos<'a>(
         &'a mut self,
@@ -85,14 +81,23 @@ impl<S: Ord> Checkpoints<S> {
     }
 }
 
-impl<S> Deref for Checkpoints<S> {
-    type Target = ArrayVec<[Checkpoint<S>; NODE_CAPACITY]>;
+impl<S, const CAP: usize> IntoIterator for Checkpoints<S, CAP> {
+    type Item = Checkpoint<S>;
+    type IntoIter = arrayvec::IntoIter<Checkpoint<S>, CAP>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<S, const CAP: usize> Deref for Checkpoints<S, CAP> {
+    type Target = ArrayVec<Checkpoint<S>, CAP>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<S> DerefMut for Checkpoints<S> {
+impl<S, const CAP: usize> DerefMut for Checkpoints<S, CAP> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
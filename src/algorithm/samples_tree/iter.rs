@@ -1,123 +1,195 @@
-use super::node::SamplesNode;
-use super::{Sample, CHILDREN_CAPACITY, NODE_CAPACITY};
-use crate::algorithm::samples_tree::{ChildrenArray, SamplesArray};
-
-pub struct IntoIter<T> {
-    samples: SamplesArray<T>,
-    children: Option<ChildrenArray<T>>,
-    next_source: NextSource<T>,
+use super::node::{Children, Leaf, Root, Trunk};
+use super::nodes::{NodesIntoIter, NodesIter};
+use super::Checkpoint;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::slice;
+
+/// An iterator over all the checkpoints of a tree, in sorted order
+pub struct IntoIter<S, const CAP: usize> {
+    // The stack of nodes being visited, from the root to the deepest node.
+    // Once empty, only the maximum checkpoint is left
+    stack: Vec<IntoIterFrame<S, CAP>>,
+    max_checkpoint: Option<Checkpoint<S>>,
+}
+
+/// The remaining checkpoints and children of a node being consumed
+struct IntoIterFrame<S, const CAP: usize> {
+    checkpoints: arrayvec::IntoIter<Checkpoint<S>, CAP>,
+    children: Option<ChildrenIntoIter<S, CAP>>,
 }
 
-enum NextSource<T> {
-    Sample,
-    Child(Box<IntoIter<T>>),
+enum ChildrenIntoIter<S, const CAP: usize> {
+    Leafs(NodesIntoIter<Leaf<S, CAP>, CAP>),
+    Trunks(NodesIntoIter<Trunk<S, CAP>, CAP>),
 }
 
-impl<T> IntoIter<T> {
-    pub fn new(samples: SamplesArray<T>, mut children: Option<ChildrenArray<T>>) -> Self {
-        let next_source = IntoIter::prepare_next_child(&mut children);
+impl<S, const CAP: usize> IntoIter<S, CAP> {
+    pub fn new(root: Root<S, CAP>, max_checkpoint: Option<Checkpoint<S>>) -> Self {
+        let root_frame = match root {
+            Root::Leaf(leaf) => IntoIterFrame::from_leaf(leaf),
+            Root::Trunk(trunk) => IntoIterFrame::from_trunk(trunk),
+        };
+
+        let mut it = IntoIter {
+            stack: vec![root_frame],
+            max_checkpoint,
+        };
+        it.descend();
+        it
+    }
 
-        IntoIter {
-            samples,
-            children,
-            next_source,
+    /// Walk down to the deepest node, following the next child of each node
+    fn descend(&mut self) {
+        while let Some(child_frame) = self.stack.last_mut().and_then(IntoIterFrame::next_child) {
+            self.stack.push(child_frame);
         }
     }
+}
 
-    fn prepare_next_child(children: &mut Option<ChildrenArray<T>>) -> NextSource<T> {
-        todo!()
-        // match children {
-        //     Some(some_children) => {
-        //         let next_source =
-        //             NextSource::Child(Box::new(some_children.pop_front().into_iter()));
-        //         if some_children.is_empty() {
-        //             // No more child nodes
-        //             *children = None;
-        //         }
-        //         next_source
-        //     }
-        //     None => NextSource::Sample,
-        // }
+impl<S, const CAP: usize> IntoIterFrame<S, CAP> {
+    fn from_leaf(leaf: Leaf<S, CAP>) -> Self {
+        IntoIterFrame {
+            checkpoints: leaf.into_checkpoints().into_iter(),
+            children: None,
+        }
+    }
+
+    fn from_trunk(trunk: Trunk<S, CAP>) -> Self {
+        let (checkpoints, children) = trunk.into_parts();
+        let children = match children {
+            Children::Leafs(leafs) => ChildrenIntoIter::Leafs(leafs.into_iter()),
+            Children::Trunks(trunks) => ChildrenIntoIter::Trunks(trunks.into_iter()),
+        };
+        IntoIterFrame {
+            checkpoints: checkpoints.into_iter(),
+            children: Some(children),
+        }
+    }
+
+    /// Prepare the next child of this node to be consumed, if any
+    fn next_child(&mut self) -> Option<Self> {
+        match self.children.as_mut()? {
+            ChildrenIntoIter::Leafs(leafs) => leafs.next().map(|leaf| Self::from_leaf(*leaf)),
+            ChildrenIntoIter::Trunks(trunks) => trunks.next().map(|trunk| Self::from_trunk(*trunk)),
+        }
     }
 }
 
-impl<T> Iterator for IntoIter<T> {
-    type Item = Sample<T>;
+impl<S, const CAP: usize> Iterator for IntoIter<S, CAP> {
+    type Item = Checkpoint<S>;
+
     fn next(&mut self) -> Option<Self::Item> {
-        todo!()
-        // loop {
-        //     match &mut self.next_source {
-        //         NextSource::Child(child_iter) => {
-        //             match child_iter.next() {
-        //                 next @ Some(_) => return next,
-        //                 None => {
-        //                     // This child finished
-        //                     self.next_source = NextSource::Sample;
-        //                 }
-        //             }
-        //         }
-        //         NextSource::Sample => {
-        //             if self.samples.is_empty() {
-        //                 return None;
-        //             }
-        //             let next = self.samples.pop_front();
-        //             self.next_source = IntoIter::prepare_next_child(&mut self.children);
-        //             return Some(next);
-        //         }
-        //     }
-        // }
+        loop {
+            let last_frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => return self.max_checkpoint.take(),
+            };
+
+            match last_frame.checkpoints.next() {
+                Some(checkpoint) => {
+                    // The next values come from the following child, if any
+                    self.descend();
+                    return Some(checkpoint);
+                }
+                None => {
+                    // Reached end of the node at the end of the stack
+                    self.stack.pop();
+                }
+            }
+        }
     }
 }
 
-pub struct Iter<'a, T> {
-    // The stack point to the next value to return.
-    // Once empty, iteration is over
-    stack: Vec<(&'a SamplesNode<T>, usize)>,
+/// An iterator over a reference to all the checkpoints of a tree, in sorted order
+pub struct Iter<'a, S, const CAP: usize> {
+    // The stack of nodes being visited, from the root to the deepest node.
+    // Once empty, only the maximum checkpoint is left
+    stack: Vec<IterFrame<'a, S, CAP>>,
+    max_checkpoint: Option<&'a Checkpoint<S>>,
 }
 
-impl<'a, T> Iter<'a, T> {
-    pub fn new(node: &'a SamplesNode<T>, tree_depth: usize) -> Self {
-        let stack = Vec::with_capacity(tree_depth);
-        let mut it = Iter { stack };
-        it.descend(node);
+/// The remaining checkpoints and children of a node being visited
+struct IterFrame<'a, S, const CAP: usize> {
+    checkpoints: slice::Iter<'a, Checkpoint<S>>,
+    children: Option<ChildrenIter<'a, S, CAP>>,
+}
+
+enum ChildrenIter<'a, S, const CAP: usize> {
+    Leafs(NodesIter<'a, Leaf<S, CAP>>),
+    Trunks(NodesIter<'a, Trunk<S, CAP>>),
+}
+
+impl<'a, S, const CAP: usize> Iter<'a, S, CAP> {
+    pub fn new(root: &'a Root<S, CAP>, max_checkpoint: Option<&'a Checkpoint<S>>) -> Self {
+        let root_frame = match root {
+            Root::Leaf(leaf) => IterFrame::from_leaf(leaf),
+            Root::Trunk(trunk) => IterFrame::from_trunk(trunk),
+        };
+
+        let mut it = Iter {
+            stack: vec![root_frame],
+            max_checkpoint,
+        };
+        it.descend();
         it
     }
 
-    fn descend(&mut self, mut node: &'a SamplesNode<T>) {
-        loop {
-            self.stack.push((node, 0));
-            match &node.children {
-                None => break,
-                Some(children) => node = &children[0],
-            }
+    /// Walk down to the deepest node, following the next child of each node
+    fn descend(&mut self) {
+        while let Some(child_frame) = self.stack.last_mut().and_then(IterFrame::next_child) {
+            self.stack.push(child_frame);
         }
     }
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a Sample<T>;
+impl<'a, S, const CAP: usize> IterFrame<'a, S, CAP> {
+    fn from_leaf(leaf: &'a Leaf<S, CAP>) -> Self {
+        IterFrame {
+            checkpoints: leaf.checkpoints().iter(),
+            children: None,
+        }
+    }
+
+    fn from_trunk(trunk: &'a Trunk<S, CAP>) -> Self {
+        let children = match trunk.children() {
+            Children::Leafs(leafs) => ChildrenIter::Leafs(leafs.iter()),
+            Children::Trunks(trunks) => ChildrenIter::Trunks(trunks.iter()),
+        };
+        IterFrame {
+            checkpoints: trunk.checkpoints().iter(),
+            children: Some(children),
+        }
+    }
+
+    /// Prepare the next child of this node to be visited, if any
+    fn next_child(&mut self) -> Option<Self> {
+        match self.children.as_mut()? {
+            ChildrenIter::Leafs(leafs) => leafs.next().map(|leaf| Self::from_leaf(leaf)),
+            ChildrenIter::Trunks(trunks) => trunks.next().map(|trunk| Self::from_trunk(trunk)),
+        }
+    }
+}
+
+impl<'a, S, const CAP: usize> Iterator for Iter<'a, S, CAP> {
+    type Item = &'a Checkpoint<S>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let (last_node, last_pos) = self.stack.last_mut().unwrap();
-            let next = last_node.samples.get(*last_pos);
-
-            match next {
-                Some(_) => {
-                    *last_pos += 1;
-                    if let Some(children) = &last_node.children {
-                        // Walk to next sample of the deepest child
-                        let child = &children[*last_pos];
-                        self.descend(child);
-                    }
-                    return next;
+            let last_frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => return self.max_checkpoint.take(),
+            };
+
+            match last_frame.checkpoints.next() {
+                Some(checkpoint) => {
+                    // The next values come from the following child, if any
+                    self.descend();
+                    return Some(checkpoint);
                 }
                 None => {
                     // Reached end of the node at the end of the stack
                     self.stack.pop();
-                    if self.stack.len() == 0 {
-                        return None;
-                    }
                 }
             }
         }
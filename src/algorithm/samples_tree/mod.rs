@@ -1,14 +1,38 @@
-// mod iter;
 mod checkpoint;
 mod checkpoints;
+mod iter;
 mod node;
+mod nodes;
 mod tree;
 
-// pub use iter::{IntoIter, Iter};
 pub use checkpoint::Checkpoint;
-// pub use tree::SamplesTree;
+pub use iter::{IntoIter, Iter};
+pub use tree::SamplesTree;
 
-// Max number of elements per node (MUST be even)
-const NODE_CAPACITY: usize = 16;
+/// Default max number of checkpoints per node. Any capacity MUST be even, and each node has one
+/// more child than checkpoints
+pub(crate) const NODE_CAPACITY: usize = 16;
 
-const CHILDREN_CAPACITY: usize = NODE_CAPACITY + 1;
+/// Return whether the given capacity respects the assumptions of the split logic: an even number
+/// of checkpoints per node, so that a full node plus the new checkpoint splits into two halves and
+/// a median
+const fn is_valid_capacity(node_capacity: usize) -> bool {
+    node_capacity > 0 && node_capacity % 2 == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capacity() {
+        assert!(is_valid_capacity(NODE_CAPACITY));
+        assert!(is_valid_capacity(2));
+        assert!(is_valid_capacity(8));
+
+        // Odd node capacity
+        assert!(!is_valid_capacity(15));
+        // Empty nodes
+        assert!(!is_valid_capacity(0));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::algorithm::samples_tree::checkpoints::{Checkpoints, LeafInsertPos};
+use crate::algorithm::samples_tree::checkpoints::Checkpoints;
 use crate::algorithm::samples_tree::node::{
     Children, InsertResult, Node, Nodes, RecordResult, Root,
 };
@@ -6,11 +6,11 @@ use crate::algorithm::samples_tree::Checkpoint;
 
 /// Represents a leaf node in the B-tree sample structure
 #[derive(Debug)]
-pub struct Leaf<S> {
-    checkpoints: Checkpoints<S>,
+pub struct Leaf<S, const CAP: usize> {
+    checkpoints: Checkpoints<S, CAP>,
 }
 
-impl<S: Ord> Node<S> for Leaf<S> {
+impl<S: Ord, const CAP: usize> Node<S, CAP> for Leaf<S, CAP> {
     fn record_sample(
         &mut self,
         sample: S,
@@ -32,16 +32,41 @@ impl<S: Ord> Node<S> for Leaf<S> {
 
     fn insert_max_checkpoint(&mut self, checkpoint: Checkpoint<S>) -> InsertResult<S, Self> {
         if let Some(max) = self.checkpoints.last() {
-            debug_assert!(checkpoint > *max);
+            debug_assert!(checkpoint >= *max);
         }
         self.insert_checkpoint(checkpoint, self.checkpoints.len())
     }
 
-    fn nodes_to_children(nodes: Nodes<Self>) -> Children<S> {
+    fn record_min_sample(
+        &mut self,
+        sample: S,
+        maximal_gap: u64,
+        following: &mut Checkpoint<S>,
+    ) -> RecordResult<S, Self> {
+        if let Some((min, others)) = self.checkpoints.split_first_mut() {
+            debug_assert!(*min > sample);
+            let second = others.first_mut().unwrap_or(following);
+            if second.can_grow(maximal_gap) {
+                // This is equivalent to insert a new exact checkpoint and then merge the
+                // current min into the second one
+                second.record_before();
+                min.swap_sample(sample);
+                return RecordResult::UpdatedInPlace;
+            }
+        }
+
+        RecordResult::Inserted(self.insert_checkpoint(Checkpoint::new_exact(sample), 0))
+    }
+
+    fn min_checkpoint(&self) -> Option<&Checkpoint<S>> {
+        self.checkpoints.first()
+    }
+
+    fn nodes_to_children(nodes: Nodes<Self, CAP>) -> Children<S, CAP> {
         Children::Leafs(nodes)
     }
 
-    fn take_from_root(root: &mut Root<S>) -> Self {
+    fn take_from_root(root: &mut Root<S, CAP>) -> Self {
         match Root::take_from_root(root) {
             Root::Leaf(leaf) => leaf,
             _ => unreachable!("Invalid root node state"),
@@ -49,7 +74,7 @@ impl<S: Ord> Node<S> for Leaf<S> {
     }
 }
 
-impl<S> Leaf<S> {
+impl<S, const CAP: usize> Leaf<S, CAP> {
     /// Create a new empty leaf node
     pub fn new() -> Self {
         Leaf {
@@ -57,6 +82,16 @@ impl<S> Leaf<S> {
         }
     }
 
+    /// Return the checkpoints stored in this node
+    pub fn checkpoints(&self) -> &Checkpoints<S, CAP> {
+        &self.checkpoints
+    }
+
+    /// Consume this node, returning its checkpoints
+    pub fn into_checkpoints(self) -> Checkpoints<S, CAP> {
+        self.checkpoints
+    }
+
     /// Insert a new checkpoint into this node. If the node is full, it will be split it into
     /// (left, median, right). Self will become left and the other two values will be returned.
     fn insert_checkpoint(
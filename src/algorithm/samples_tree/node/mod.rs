@@ -1,15 +1,15 @@
-use crate::algorithm::samples_tree::{Checkpoint, CHILDREN_CAPACITY};
+use crate::algorithm::samples_tree::Checkpoint;
 
 mod leaf;
 mod root;
 mod trunk;
 
-use arrayvec::ArrayVec;
+pub use crate::algorithm::samples_tree::nodes::Nodes;
 pub use leaf::*;
 pub use root::*;
 pub use trunk::*;
 
-pub trait Node<S>: Sized {
+pub trait Node<S, const CAP: usize>: Sized {
     /// Record a new sample into this node, either by a micro-compression or by inserting a new
     /// checkpoint.
     fn record_sample(
@@ -21,27 +21,39 @@ pub trait Node<S>: Sized {
         following: &mut Checkpoint<S>,
     ) -> RecordResult<S, Self>;
 
-    /// Insert a checkpoint that is greater than all other checkpoints in this node and its
+    /// Insert a checkpoint that is greater or equal to all other checkpoints in this node and its
     /// descendants. It will panic in debug mode if this requirement does not hold true
     fn insert_max_checkpoint(&mut self, checkpoint: Checkpoint<S>) -> InsertResult<S, Self>;
 
+    /// Record a new sample that is smaller than all other checkpoints in this node and its
+    /// descendants, either by a micro-compression or by inserting a new exact checkpoint.
+    /// It will panic in debug mode if this requirement does not hold true
+    fn record_min_sample(
+        &mut self,
+        sample: S,
+        // The greatest value for `max_gap`
+        maximal_gap: u64,
+        // The following checkpoint to this node
+        following: &mut Checkpoint<S>,
+    ) -> RecordResult<S, Self>;
+
+    /// Return the least checkpoint in this node and its descendants, if any
+    fn min_checkpoint(&self) -> Option<&Checkpoint<S>>;
+
     /// Convert from a generic list of children to the tagged type
-    fn nodes_to_children(nodes: Nodes<Self>) -> Children<S>;
+    fn nodes_to_children(nodes: Nodes<Self, CAP>) -> Children<S, CAP>;
 
     /// Take a not of this type from the root node or panic trying
-    fn take_from_root(root: &mut Root<S>) -> Self;
+    fn take_from_root(root: &mut Root<S, CAP>) -> Self;
 }
 
 /// Represents the children of a non-leaf node in the B-tree sample structure
 #[derive(Debug)]
-pub enum Children<S> {
-    Leafs(Nodes<Leaf<S>>),
-    Trunks(Nodes<Trunk<S>>),
+pub enum Children<S, const CAP: usize> {
+    Leafs(Nodes<Leaf<S, CAP>, CAP>),
+    Trunks(Nodes<Trunk<S, CAP>, CAP>),
 }
 
-/// Represents generic children of a non-leaf node in the B-tree sample structure
-pub type Nodes<N> = ArrayVec<[Box<N>; CHILDREN_CAPACITY]>;
-
 #[derive(Debug)]
 pub enum RecordResult<S, N> {
     UpdatedInPlace,
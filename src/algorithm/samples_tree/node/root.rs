@@ -2,16 +2,17 @@ use crate::algorithm::samples_tree::node::{
     Children, InsertResult, Leaf, Node, Nodes, RecordResult, Trunk,
 };
 use crate::algorithm::samples_tree::Checkpoint;
-use std::mem;
+use alloc::boxed::Box;
+use core::mem;
 
 /// Represents the root node that can take many forms
 #[derive(Debug)]
-pub enum Root<S> {
-    Leaf(Leaf<S>),
-    Trunk(Trunk<S>),
+pub enum Root<S, const CAP: usize> {
+    Leaf(Leaf<S, CAP>),
+    Trunk(Trunk<S, CAP>),
 }
 
-impl<S: Ord> Node<S> for Root<S> {
+impl<S: Ord, const CAP: usize> Node<S, CAP> for Root<S, CAP> {
     fn record_sample(
         &mut self,
         sample: S,
@@ -43,17 +44,42 @@ impl<S: Ord> Node<S> for Root<S> {
         }
     }
 
-    fn nodes_to_children(_nodes: Nodes<Self>) -> Children<S> {
+    fn record_min_sample(
+        &mut self,
+        sample: S,
+        maximal_gap: u64,
+        following: &mut Checkpoint<S>,
+    ) -> RecordResult<S, Self> {
+        match self {
+            Root::Leaf(leaf) => {
+                let result = leaf.record_min_sample(sample, maximal_gap, following);
+                self.generic_handle_record_result(result)
+            }
+            Root::Trunk(trunk) => {
+                let result = trunk.record_min_sample(sample, maximal_gap, following);
+                self.generic_handle_record_result(result)
+            }
+        }
+    }
+
+    fn min_checkpoint(&self) -> Option<&Checkpoint<S>> {
+        match self {
+            Root::Leaf(leaf) => leaf.min_checkpoint(),
+            Root::Trunk(trunk) => trunk.min_checkpoint(),
+        }
+    }
+
+    fn nodes_to_children(_nodes: Nodes<Self, CAP>) -> Children<S, CAP> {
         unreachable!("there should be only a single root")
     }
 
-    fn take_from_root(root: &mut Root<S>) -> Self {
+    fn take_from_root(root: &mut Root<S, CAP>) -> Self {
         mem::replace(root, Root::Leaf(Leaf::new()))
     }
 }
 
-impl<S: Ord> Root<S> {
-    fn generic_handle_record_result<N: Node<S>>(
+impl<S: Ord, const CAP: usize> Root<S, CAP> {
+    fn generic_handle_record_result<N: Node<S, CAP>>(
         &mut self,
         result: RecordResult<S, N>,
     ) -> RecordResult<S, Self> {
@@ -65,7 +91,7 @@ impl<S: Ord> Root<S> {
         }
     }
 
-    fn generic_handle_insert_result<N: Node<S>>(
+    fn generic_handle_insert_result<N: Node<S, CAP>>(
         &mut self,
         result: InsertResult<S, N>,
     ) -> InsertResult<S, Self> {
@@ -83,7 +109,7 @@ impl<S: Ord> Root<S> {
     }
 }
 
-impl<S> Root<S> {
+impl<S, const CAP: usize> Root<S, CAP> {
     #[cfg(test)]
     pub fn depth(&self) -> usize {
         match self {
@@ -2,17 +2,17 @@ use crate::algorithm::samples_tree::checkpoints::Checkpoints;
 use crate::algorithm::samples_tree::node::{
     Children, InsertResult, Node, Nodes, RecordResult, Root,
 };
-use crate::algorithm::samples_tree::{Checkpoint, CHILDREN_CAPACITY};
-use arrayvec::ArrayVec;
+use crate::algorithm::samples_tree::Checkpoint;
+use alloc::boxed::Box;
 
 /// Represents a non-leaf node in the B-tree sample structure
 #[derive(Debug)]
-pub struct Trunk<S> {
-    checkpoints: Checkpoints<S>,
-    children: Children<S>,
+pub struct Trunk<S, const CAP: usize> {
+    checkpoints: Checkpoints<S, CAP>,
+    children: Children<S, CAP>,
 }
 
-impl<S: Ord> Node<S> for Trunk<S> {
+impl<S: Ord, const CAP: usize> Node<S, CAP> for Trunk<S, CAP> {
     fn record_sample(
         &mut self,
         sample: S,
@@ -48,11 +48,34 @@ impl<S: Ord> Node<S> for Trunk<S> {
         }
     }
 
-    fn nodes_to_children(nodes: Nodes<Self>) -> Children<S> {
+    fn record_min_sample(
+        &mut self,
+        sample: S,
+        maximal_gap: u64,
+        _following: &mut Checkpoint<S>,
+    ) -> RecordResult<S, Self> {
+        match &mut self.children {
+            Children::Leafs(leafs) => {
+                Trunk::generic_record_min_sample(&mut self.checkpoints, leafs, sample, maximal_gap)
+            }
+            Children::Trunks(trunks) => {
+                Trunk::generic_record_min_sample(&mut self.checkpoints, trunks, sample, maximal_gap)
+            }
+        }
+    }
+
+    fn min_checkpoint(&self) -> Option<&Checkpoint<S>> {
+        match &self.children {
+            Children::Leafs(leafs) => leafs.first().min_checkpoint(),
+            Children::Trunks(trunks) => trunks.first().min_checkpoint(),
+        }
+    }
+
+    fn nodes_to_children(nodes: Nodes<Self, CAP>) -> Children<S, CAP> {
         Children::Trunks(nodes)
     }
 
-    fn take_from_root(root: &mut Root<S>) -> Self {
+    fn take_from_root(root: &mut Root<S, CAP>) -> Self {
         match Root::take_from_root(root) {
             Root::Trunk(trunk) => trunk,
             _ => unreachable!("Invalid root node state"),
@@ -60,10 +83,10 @@ impl<S: Ord> Node<S> for Trunk<S> {
     }
 }
 
-impl<S: Ord> Trunk<S> {
-    fn generic_record_sample<N: Node<S>>(
-        checkpoints: &mut Checkpoints<S>,
-        nodes: &mut Nodes<N>,
+impl<S: Ord, const CAP: usize> Trunk<S, CAP> {
+    fn generic_record_sample<N: Node<S, CAP>>(
+        checkpoints: &mut Checkpoints<S, CAP>,
+        nodes: &mut Nodes<N, CAP>,
         sample: S,
         maximal_gap: u64,
         following: &mut Checkpoint<S>,
@@ -73,7 +96,7 @@ impl<S: Ord> Trunk<S> {
         use InsertResult::*;
         use RecordResult::*;
 
-        let node = &mut nodes[pos];
+        let node = nodes.get_mut(pos);
         match node.record_sample(sample, maximal_gap, following) {
             // Explicit pass-through to convert `RecordResult<S, N>` into `RecordResult<S, Self>`
             Inserted(Done) => Inserted(Done),
@@ -90,14 +113,14 @@ impl<S: Ord> Trunk<S> {
         }
     }
 
-    fn generic_insert_max_checkpoint<N: Node<S>>(
-        checkpoints: &mut Checkpoints<S>,
-        nodes: &mut Nodes<N>,
+    fn generic_insert_max_checkpoint<N: Node<S, CAP>>(
+        checkpoints: &mut Checkpoints<S, CAP>,
+        nodes: &mut Nodes<N, CAP>,
         checkpoint: Checkpoint<S>,
     ) -> InsertResult<S, Self> {
         use InsertResult::*;
 
-        let last = nodes.last_mut().expect("nodes is not empty");
+        let last = nodes.last_mut();
         match last.insert_max_checkpoint(checkpoint) {
             // Explicit pass-through to convert `InsertResult<S, N>` into `InsertResult<S, Self>`
             Done => Done,
@@ -110,16 +133,43 @@ impl<S: Ord> Trunk<S> {
             ),
         }
     }
+
+    fn generic_record_min_sample<N: Node<S, CAP>>(
+        checkpoints: &mut Checkpoints<S, CAP>,
+        nodes: &mut Nodes<N, CAP>,
+        sample: S,
+        maximal_gap: u64,
+    ) -> RecordResult<S, Self> {
+        use InsertResult::*;
+        use RecordResult::*;
+
+        // The minimum lives in the first child, which is followed by the first checkpoint
+        let following = checkpoints.first_mut().expect("checkpoints is not empty");
+        let first = nodes.first_mut();
+        match first.record_min_sample(sample, maximal_gap, following) {
+            // Explicit pass-through to convert `RecordResult<S, N>` into `RecordResult<S, Self>`
+            Inserted(Done) => Inserted(Done),
+            UpdatedInPlace => UpdatedInPlace,
+            Inserted(Pending(med_checkpoint, right_node)) => {
+                Inserted(Self::generic_insert_checkpoint(
+                    checkpoints,
+                    nodes,
+                    med_checkpoint,
+                    Box::new(right_node),
+                    0,
+                ))
+            }
+        }
+    }
 }
 
-impl<S> Trunk<S> {
-    pub fn with_median<N: Node<S>>(
+impl<S, const CAP: usize> Trunk<S, CAP> {
+    pub fn with_median<N: Node<S, CAP>>(
         left_node: Box<N>,
         med_checkpoint: Checkpoint<S>,
         right_node: Box<N>,
     ) -> Self {
-        let mut nodes: Nodes<N> = ArrayVec::new();
-        nodes.push(left_node);
+        let mut nodes = Nodes::new(left_node);
         nodes.push(right_node);
 
         let mut checkpoints = Checkpoints::new();
@@ -128,7 +178,25 @@ impl<S> Trunk<S> {
         Self::with_children(checkpoints, nodes)
     }
 
-    fn with_children<N: Node<S>>(checkpoints: Checkpoints<S>, nodes: Nodes<N>) -> Self {
+    /// Return the checkpoints stored in this node
+    pub fn checkpoints(&self) -> &Checkpoints<S, CAP> {
+        &self.checkpoints
+    }
+
+    /// Return the children of this node
+    pub fn children(&self) -> &Children<S, CAP> {
+        &self.children
+    }
+
+    /// Consume this node, returning its checkpoints and children
+    pub fn into_parts(self) -> (Checkpoints<S, CAP>, Children<S, CAP>) {
+        (self.checkpoints, self.children)
+    }
+
+    fn with_children<N: Node<S, CAP>>(
+        checkpoints: Checkpoints<S, CAP>,
+        nodes: Nodes<N, CAP>,
+    ) -> Self {
         debug_assert_eq!(checkpoints.len() + 1, nodes.len());
         let children = N::nodes_to_children(nodes);
         Trunk {
@@ -139,9 +207,9 @@ impl<S> Trunk<S> {
 
     /// Insert a new checkpoint into this node. If the node is full, it will be split it into
     /// (left, median, right). Self will become left and the other two values will be returned.
-    fn generic_insert_checkpoint<N: Node<S>>(
-        checkpoints: &mut Checkpoints<S>,
-        nodes: &mut Nodes<N>,
+    fn generic_insert_checkpoint<N: Node<S, CAP>>(
+        checkpoints: &mut Checkpoints<S, CAP>,
+        nodes: &mut Nodes<N, CAP>,
         med_checkpoint: Checkpoint<S>,
         right_node: Box<N>,
         pos: usize,
@@ -155,12 +223,12 @@ impl<S> Trunk<S> {
             }
             Pending(new_med_checkpoint, right_checkpoints) => {
                 let med_pos = nodes.len() / 2;
-                let mut right_children: Nodes<N>;
+                let mut right_children: Nodes<N, CAP>;
                 if pos < med_pos {
-                    right_children = nodes.drain(med_pos..).collect();
+                    right_children = nodes.split_off(med_pos);
                     nodes.insert(pos + 1, right_node);
                 } else {
-                    right_children = nodes.drain(med_pos + 1..).collect();
+                    right_children = nodes.split_off(med_pos + 1);
                     right_children.insert(pos - med_pos, right_node);
                 }
 
@@ -176,7 +244,7 @@ impl<S> Trunk<S> {
     pub fn depth(&self) -> usize {
         match &self.children {
             Children::Leafs(_) => 2,
-            Children::Trunks(trunks) => 1 + trunks[0].depth(),
+            Children::Trunks(trunks) => 1 + trunks.first().depth(),
         }
     }
 }
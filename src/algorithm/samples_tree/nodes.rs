@@ -0,0 +1,137 @@
+use alloc::boxed::Box;
+use arrayvec::ArrayVec;
+use core::iter::{self, Chain, Once};
+use core::slice;
+
+/// The children of a non-leaf node, which has one more child than its (at most `CAP`) checkpoints.
+///
+/// Expressions like `CAP + 1` are not allowed as const generic arguments yet, so the first child
+/// is stored apart from the following ones. A side effect is that the list is never empty.
+#[derive(Debug)]
+pub struct Nodes<N, const CAP: usize> {
+    first: Box<N>,
+    // The children following each checkpoint of the node
+    others: ArrayVec<Box<N>, CAP>,
+}
+
+/// An iterator over a reference to the children of a node, in order
+pub type NodesIter<'a, N> = Chain<Once<&'a Box<N>>, slice::Iter<'a, Box<N>>>;
+
+/// An iterator over the children of a node, in order
+pub type NodesIntoIter<N, const CAP: usize> = Chain<Once<Box<N>>, arrayvec::IntoIter<Box<N>, CAP>>;
+
+impl<N, const CAP: usize> Nodes<N, CAP> {
+    /// Create a new list with a single child
+    pub fn new(first: Box<N>) -> Self {
+        Nodes {
+            first,
+            others: ArrayVec::new(),
+        }
+    }
+
+    /// Return the number of children
+    pub fn len(&self) -> usize {
+        self.others.len() + 1
+    }
+
+    /// Return the first child
+    pub fn first(&self) -> &N {
+        &self.first
+    }
+
+    /// Return the first child
+    pub fn first_mut(&mut self) -> &mut N {
+        &mut self.first
+    }
+
+    /// Return the last child
+    pub fn last_mut(&mut self) -> &mut N {
+        match self.others.last_mut() {
+            Some(last) => last,
+            None => &mut self.first,
+        }
+    }
+
+    /// Return the child at the given position. Panics if out of bounds
+    pub fn get_mut(&mut self, pos: usize) -> &mut N {
+        match pos {
+            0 => &mut self.first,
+            _ => &mut self.others[pos - 1],
+        }
+    }
+
+    /// Add a child at the end of the list. Panics if the list is full
+    pub fn push(&mut self, node: Box<N>) {
+        self.others.push(node);
+    }
+
+    /// Insert a child at the given position, shifting the following ones. Panics if the list is
+    /// full or the position is out of bounds
+    pub fn insert(&mut self, pos: usize, mut node: Box<N>) {
+        if pos == 0 {
+            core::mem::swap(&mut self.first, &mut node);
+            self.others.insert(0, node);
+        } else {
+            self.others.insert(pos - 1, node);
+        }
+    }
+
+    /// Splits the list into two at the given position, which must not be zero.
+    ///
+    /// Returns a new list containing the elements in the range [at, len). After the call, the
+    /// original list will be left containing the elements [0, at).
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at > 0, "both sides must be non-empty");
+        let mut drained = self.others.drain(at - 1..);
+        let first = drained.next().expect("right side is non-empty");
+        Nodes {
+            first,
+            others: drained.collect(),
+        }
+    }
+
+    /// Return an iterator over a reference to the children
+    pub fn iter(&self) -> NodesIter<'_, N> {
+        iter::once(&self.first).chain(self.others.iter())
+    }
+}
+
+impl<N, const CAP: usize> IntoIterator for Nodes<N, CAP> {
+    type Item = Box<N>;
+    type IntoIter = NodesIntoIter<N, CAP>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        iter::once(self.first).chain(self.others)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn values<const CAP: usize>(nodes: &Nodes<u32, CAP>) -> Vec<u32> {
+        nodes.iter().map(|node| **node).collect()
+    }
+
+    #[test]
+    fn insert_and_split() {
+        // Fill all the `CAP + 1` slots
+        let mut nodes = Nodes::<_, 4>::new(Box::new(1));
+        nodes.push(Box::new(3));
+        nodes.insert(0, Box::new(0));
+        nodes.insert(2, Box::new(2));
+        nodes.insert(4, Box::new(4));
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(values(&nodes), vec![0, 1, 2, 3, 4]);
+        assert_eq!(*nodes.get_mut(3), 3);
+        assert_eq!(*nodes.last_mut(), 4);
+
+        let right = nodes.split_off(2);
+        assert_eq!(values(&nodes), vec![0, 1]);
+        assert_eq!(values(&right), vec![2, 3, 4]);
+        let right: Vec<_> = right.into_iter().map(|node| *node).collect();
+        assert_eq!(right, vec![2, 3, 4]);
+    }
+}
@@ -1,47 +1,132 @@
-use crate::algorithm::samples_tree::node::{InsertResult, Leaf, Node, RecordResult, Root, Trunk};
-use crate::algorithm::samples_tree::Checkpoint;
-use std::mem;
+use crate::algorithm::samples_tree::node::{Leaf, Node, RecordResult, Root};
+use crate::algorithm::samples_tree::{Checkpoint, IntoIter, Iter};
+use crate::quantile_to_rank;
+use core::mem;
 
 /// Represents a tree that records samples into checkpoints
 #[derive(Debug)]
-pub struct SamplesTree<S> {
-    // Store a clone of the minimum sample and the maximum checkpoint separately, because they
-    // require special logic
-    extremes: Option<(S, Checkpoint<S>)>,
-    root: Root<S>,
+pub struct SamplesTree<S, const CAP: usize> {
+    // Store the maximum checkpoint separately, because it requires special logic.
+    // The minimum sample is always kept as an exact checkpoint at the start of the root node
+    max_checkpoint: Option<Checkpoint<S>>,
+    root: Root<S, CAP>,
     // Total number of checkpoints, including the one store at the maximum extreme
     num_checkpoints: usize,
 }
 
-impl<S> SamplesTree<S> {
+impl<S, const CAP: usize> SamplesTree<S, CAP> {
     /// Create a new empty tree
     pub fn new() -> Self {
         SamplesTree {
-            extremes: None,
+            max_checkpoint: None,
             root: Root::Leaf(Leaf::new()),
             num_checkpoints: 0,
         }
     }
 
+    /// Return the number of stored checkpoints in the whole tree
+    pub fn len(&self) -> usize {
+        self.num_checkpoints
+    }
+
+    /// Return whether the tree has no checkpoints
+    pub fn is_empty(&self) -> bool {
+        self.num_checkpoints == 0
+    }
+
+    /// Create a iterator over a reference to all the checkpoints in sorted order
+    pub fn iter(&self) -> Iter<'_, S, CAP> {
+        Iter::new(&self.root, self.max_checkpoint.as_ref())
+    }
+
+    /// Query for a desired quantile, where `len` is the number of recorded samples.
+    /// Return None if and only if the tree is empty
+    pub fn query(&self, quantile: f64, len: u64) -> Option<&S> {
+        self.query_with_error(quantile, len)
+            .map(|(sample, _max_rank_error)| sample)
+    }
+
+    /// Query for a desired quantile, where `len` is the number of recorded samples, also returning
+    /// the greatest possible distance between the answer's rank and the desired one.
+    /// Return None if and only if the tree is empty
+    pub fn query_with_error(&self, quantile: f64, len: u64) -> Option<(&S, u64)> {
+        self.query_many([quantile], len).map(|[answer]| answer)
+    }
+
+    /// Query for several quantiles at once, with a single walk over the checkpoints. Each answer is
+    /// the same as the one given by `query_with_error()`.
+    /// Return None if and only if the tree is empty
+    pub fn query_many<const N: usize>(
+        &self,
+        quantiles: [f64; N],
+        len: u64,
+    ) -> Option<[(&S, u64); N]> {
+        // Find, for each quantile, the checkpoint with the smallest maximum rank error
+
+        if self.is_empty() {
+            return None;
+        }
+
+        let target_ranks = quantiles.map(|quantile| quantile_to_rank(quantile, len));
+        let mut best: [Option<(&S, u64)>; N] = [None; N];
+        let mut min_rank = 0;
+
+        for checkpoint in self.iter() {
+            // This checkpoint's rank is in [min_rank, max_rank] (inclusive in both sides)
+            min_rank += checkpoint.min_gap();
+            let max_rank = min_rank + checkpoint.max_gap() - checkpoint.min_gap();
+            let mid_rank = (min_rank + max_rank) / 2;
+
+            for (&target_rank, best) in target_ranks.iter().zip(best.iter_mut()) {
+                // In the worst case, the correct sample's rank is at the opposite extremity
+                let max_rank_error = if target_rank > mid_rank {
+                    target_rank - min_rank
+                } else {
+                    max_rank - target_rank
+                };
+
+                // Keep the first best answer
+                match best {
+                    Some((_sample, best_error)) if *best_error <= max_rank_error => {}
+                    _ => *best = Some((checkpoint.sample(), max_rank_error)),
+                }
+            }
+        }
+
+        // There is at least one checkpoint, so every quantile got an answer
+        Some(best.map(|answer| answer.unwrap()))
+    }
+
     #[cfg(test)]
     fn depth(&self) -> usize {
         self.root.depth()
     }
 }
 
-impl<S: Ord + Clone> SamplesTree<S> {
+impl<S, const CAP: usize> IntoIterator for SamplesTree<S, CAP> {
+    type Item = Checkpoint<S>;
+    type IntoIter = IntoIter<S, CAP>;
+
+    /// Create a iterator over all the checkpoints in sorted order
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.root, self.max_checkpoint)
+    }
+}
+
+impl<S: Ord, const CAP: usize> SamplesTree<S, CAP> {
     /// Record a new sample into this tree, either by a micro-compression or by inserting a new
     /// checkpoint.
     pub fn record_sample(&mut self, sample: S, maximal_gap: u64) {
-        match &mut self.extremes {
+        match &mut self.max_checkpoint {
             None => {
                 // First sample
-                self.extremes = Some((sample.clone(), Checkpoint::new_exact(sample)));
+                self.max_checkpoint = Some(Checkpoint::new_exact(sample));
                 self.num_checkpoints += 1;
             }
-            Some((_, max_checkpoint)) if *max_checkpoint <= sample => {
-                // A new global maximum: check for in-place compression
-                if max_checkpoint.can_grow(maximal_gap) {
+            Some(max_checkpoint) if *max_checkpoint <= sample => {
+                // A new global maximum: check for in-place compression. This is only possible
+                // when the current max is not also the global minimum, which must be kept
+                if self.num_checkpoints > 1 && max_checkpoint.can_grow(maximal_gap) {
                     // This is equivalent to insert a new exact checkpoint and then merge the
                     // current max into it
                     max_checkpoint.record_before();
@@ -53,33 +138,69 @@ impl<S: Ord + Clone> SamplesTree<S> {
                     self.num_checkpoints += 1;
                 }
             }
-            Some((min_sample, max_checkpoint)) => {
-                if *min_sample > sample {
-                    // A new global minimum: store it and then apply the general case.
-                    // Storing the global minimum is needed to guarantee that small-quantile
-                    // queries respect the maximum relative error
-                    *min_sample = sample.clone();
-                }
+            Some(max_checkpoint) => {
+                let is_global_min = match self.root.min_checkpoint() {
+                    None => true,
+                    Some(min_checkpoint) => *min_checkpoint > sample,
+                };
 
-                // Generic case
-                if let RecordResult::Inserted(_) =
+                // A new global minimum is always stored as an exact checkpoint, possibly by
+                // in-place compression. Keeping the global minimum is needed to guarantee that
+                // small-quantile queries respect the maximum relative error
+                let result = if is_global_min {
+                    self.root
+                        .record_min_sample(sample, maximal_gap, max_checkpoint)
+                } else {
+                    // Generic case
                     self.root.record_sample(sample, maximal_gap, max_checkpoint)
-                {
+                };
+
+                if let RecordResult::Inserted(_) = result {
                     self.num_checkpoints += 1;
                 }
             }
         }
     }
+
+    /// Insert a checkpoint that is greater or equal to all other checkpoints in this tree.
+    /// This allows for a performant population of the tree from a sorted stream of checkpoints.
+    /// It will panic in debug mode if this requirement does not hold true
+    pub fn insert_max_checkpoint(&mut self, checkpoint: Checkpoint<S>) {
+        if let Some(prev_max_checkpoint) = self.max_checkpoint.take() {
+            debug_assert!(checkpoint >= prev_max_checkpoint);
+            self.root.insert_max_checkpoint(prev_max_checkpoint);
+        }
+        self.max_checkpoint = Some(checkpoint);
+        self.num_checkpoints += 1;
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::algorithm::samples_tree::NODE_CAPACITY;
+    use crate::rank_to_quantile;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn len() {
+        let mut tree = SamplesTree::<_, NODE_CAPACITY>::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+
+        // Enough to split the root leaf
+        let n = NODE_CAPACITY + 1;
+        for i in 0..n {
+            tree.record_sample(i, 1);
+        }
+        assert!(!tree.is_empty());
+        assert_eq!(tree.len(), n);
+    }
 
     #[test]
     fn record_asc_depth_1() {
-        let mut tree = SamplesTree::new();
+        let mut tree = SamplesTree::<_, NODE_CAPACITY>::new();
 
         // One full leaf root node
         let n = NODE_CAPACITY;
@@ -92,7 +213,7 @@ mod test {
 
     #[test]
     fn record_asc_depth_2() {
-        let mut tree = SamplesTree::new();
+        let mut tree = SamplesTree::<_, NODE_CAPACITY>::new();
 
         // One full trunk root node with N half-full leaf nodes and a full leaf node
         let n = NODE_CAPACITY + NODE_CAPACITY * (NODE_CAPACITY / 2) + NODE_CAPACITY;
@@ -105,7 +226,7 @@ mod test {
 
     #[test]
     fn record_asc_depth_3() {
-        let mut tree = SamplesTree::new();
+        let mut tree = SamplesTree::<_, NODE_CAPACITY>::new();
 
         // One full trunk root node with:
         // N x half-full trunk node with (N/2+1) x half-full leaf nodes
@@ -119,4 +240,137 @@ mod test {
         assert_eq!(tree.depth(), 3);
         assert_eq!(tree.num_checkpoints, n);
     }
+
+    #[test]
+    fn record_desc_compression() {
+        fn num_checkpoints(values: impl Iterator<Item = usize>, epsilon: f64) -> usize {
+            let mut tree = SamplesTree::<_, NODE_CAPACITY>::new();
+            for (i, value) in values.enumerate() {
+                let len = i as f64 + 1.;
+                tree.record_sample(value, (2. * epsilon * len).floor() as u64);
+            }
+            tree.len()
+        }
+
+        // A new global minimum is compressed in place just like a new global maximum
+        for &epsilon in &[0.2, 0.1, 0.01] {
+            for &n in &[10, 100, 1000, 10_000] {
+                let asc = num_checkpoints(0..n, epsilon);
+                let desc = num_checkpoints((0..n).rev(), epsilon);
+                assert_eq!(asc, desc, "epsilon={}, n={}", epsilon, n);
+                if 2. * epsilon * n as f64 >= 2. {
+                    assert!(desc < n, "epsilon={}, n={}", epsilon, n);
+                }
+            }
+        }
+    }
+
+    /// Build a tree from exact samples (use maximal_gap = 1 to keep all of them) and check that
+    /// both iterators yield them in sorted order. Return the depth of the tree
+    fn check_iterators<const CAP: usize>(mut values: Vec<usize>) -> usize {
+        let mut tree = SamplesTree::<_, CAP>::new();
+        for &value in &values {
+            tree.record_sample(value, 1);
+        }
+        let depth = tree.depth();
+
+        // Collect from by-ref and by-value iterators
+        let collected_by_ref: Vec<usize> =
+            tree.iter().map(|checkpoint| *checkpoint.sample()).collect();
+        let collected_by_value: Vec<usize> = tree
+            .into_iter()
+            .map(|checkpoint| *checkpoint.sample())
+            .collect();
+
+        values.sort();
+        assert_eq!(values, collected_by_ref);
+        assert_eq!(values, collected_by_value);
+
+        depth
+    }
+
+    /// Build a tree with the given maximum error and check the result of querying for every rank
+    fn check_query<const CAP: usize>(values: Vec<usize>, epsilon: f64) {
+        let mut tree = SamplesTree::<_, CAP>::new();
+        assert_eq!(tree.query(0.5, 0), None);
+
+        for (i, &value) in values.iter().enumerate() {
+            let len = i as f64 + 1.;
+            tree.record_sample(value, (2. * epsilon * len).floor() as u64);
+        }
+
+        let len = values.len() as u64;
+        let mut sorted_values = values;
+        sorted_values.sort();
+
+        for rank in 1..=len {
+            let quantile = rank_to_quantile(rank, len);
+            let target_rank = quantile_to_rank(quantile, len);
+            let queried = tree.query(quantile, len).unwrap();
+            let got_rank = sorted_values.binary_search(queried).unwrap() as u64 + 1;
+            let error = (got_rank as f64 - target_rank as f64).abs() / len as f64;
+            assert!(
+                error <= epsilon,
+                "target_rank={}, got_rank={}, error={}",
+                target_rank,
+                got_rank,
+                error
+            );
+        }
+
+        assert_eq!(tree.query(0., len), sorted_values.first());
+        assert_eq!(tree.query(1., len), sorted_values.last());
+    }
+
+    #[test]
+    fn iterators() {
+        let check = |values, depth| assert_eq!(check_iterators::<NODE_CAPACITY>(values), depth);
+
+        // Empty tree
+        check(vec![], 1);
+
+        // Leaf tree
+        check((0..NODE_CAPACITY).collect(), 1);
+
+        // Tree with two levels
+        check((0..NODE_CAPACITY * NODE_CAPACITY / 4).collect(), 2);
+
+        // Tree with three levels, in ascending, descending and shuffled order
+        let n = NODE_CAPACITY * NODE_CAPACITY * 4;
+        check((0..n).collect(), 3);
+        check((0..n).rev().collect(), 3);
+        check((0..n).map(|i| i * 7919 % n).collect(), 3);
+    }
+
+    #[test]
+    fn query() {
+        let check = check_query::<NODE_CAPACITY>;
+        for &epsilon in &[0.2, 0.1, 0.01] {
+            for &n in &[1, 10, 100, 1000] {
+                check((0..n).collect(), epsilon);
+                check((0..n).rev().collect(), epsilon);
+                check((0..n).map(|i| i * 7919 % n).collect(), epsilon);
+            }
+        }
+    }
+
+    #[test]
+    fn other_capacities() {
+        fn check<const CAP: usize>() {
+            // Enough values to build trees with at least three levels
+            let n = CAP * CAP * 4;
+            assert!(check_iterators::<CAP>((0..n).collect()) >= 3);
+            assert!(check_iterators::<CAP>((0..n).rev().collect()) >= 3);
+            assert!(check_iterators::<CAP>((0..n).map(|i| i * 7919 % n).collect()) >= 3);
+
+            for &epsilon in &[0.1, 0.01] {
+                check_query::<CAP>((0..n).collect(), epsilon);
+                check_query::<CAP>((0..n).rev().collect(), epsilon);
+                check_query::<CAP>((0..n).map(|i| i * 7919 % n).collect(), epsilon);
+            }
+        }
+
+        check::<8>();
+        check::<32>();
+    }
 }
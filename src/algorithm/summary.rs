@@ -1,105 +1,187 @@
 use super::incoming_merge_state::IncomingMergeState;
 use super::samples_compressor::SamplesCompressor;
-use super::samples_tree::{Sample, SamplesTree};
-use crate::quantile_to_rank;
-use std::mem;
+use super::samples_tree::{Checkpoint, SamplesTree, NODE_CAPACITY};
+use core::mem;
 
 /// Implement a modified version of the algorithm by Greenwald and Khanna in
 /// Space-Efficient Online Computation of Quantile Summaries
-/// TODO: describe the diferences and explain why
-pub struct Summary<T: Ord> {
-    samples_tree: SamplesTree<T>,
-    /// Maximum number of samples to keep
-    max_samples: u64,
-    /// Maximum error
+///
+/// The differences to the original algorithm are:
+/// - the stored samples are kept in a B-tree instead of a list, so that each insertion costs
+///   `O(log n)` instead of `O(n)`;
+/// - instead of always storing a new sample and periodically compressing the whole structure,
+///   each insertion first tries to account for the new sample in a neighbouring stored one
+///   (micro-compression). The full compression is only a fallback for when too many samples
+///   are kept, which rarely happens;
+/// - the least and the greatest samples are always stored with their exact ranks, so the
+///   quantiles 0 and 1 are answered exactly.
+///
+/// Summaries can also be merged. Like in the original algorithm, the result is as precise as the
+/// least precise of them.
+///
+/// This uses the default node capacity, see `SummaryWithCapacity` to tune it
+pub type Summary<S> = SummaryWithCapacity<S, NODE_CAPACITY>;
+
+/// A panel of commonly used quantiles, as returned by `SummaryWithCapacity::stats()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryStats<S> {
+    /// The least sample, that is, the quantile 0
+    pub min: S,
+    /// The median, that is, the quantile 0.5
+    pub p50: S,
+    /// The quantile 0.9
+    pub p90: S,
+    /// The quantile 0.95
+    pub p95: S,
+    /// The quantile 0.99
+    pub p99: S,
+    /// The greatest sample, that is, the quantile 1
+    pub max: S,
+}
+
+/// Configure and create a `SummaryWithCapacity`
+///
+/// # Example
+/// ```
+/// use fast_quantiles::{Summary, SummaryBuilder};
+/// // Use less memory, at the cost of more frequent full compressions
+/// let mut summary: Summary<u32> = SummaryBuilder::new()
+///     .max_expected_error(0.01)
+///     .max_samples_factor(2)
+///     .build();
+/// summary.insert_one(17);
+/// assert_eq!(summary.query(0.5), Some(&17));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryBuilder {
     max_expected_error: f64,
-    /// Number of samples already seen
-    len: u64,
+    max_samples_factor: u64,
 }
 
-impl<T: Ord> Summary<T> {
-    /// Create a new empty Summary
-    pub fn new(max_expected_error: f64) -> Summary<T> {
-        let expected_least_compressed_samples = (1. / max_expected_error).ceil() as u64;
-        Summary {
-            samples_tree: SamplesTree::new(),
-            // This encodes a tradeoff between using more memory and compressing more frequently.
-            // However, with the implemented micro-compression at every insert, in the worst case
-            // (sorted stream of values), the structure will accumulate all of the `F=1/eps` first
-            // elements, then half of the next `F/2`, then a third of the next `F/2`, and so on.
-            // This means that in the worst case we'll reach:
-            // | saved samples | saw samples |
-            // |        1.00 F |           F |
-            // |        2.01 F |         6 F |
-            // |        3.00 F |        42 F |
-            // |        4.00 F |       309 F |
-            // |        5.00 F |      2276 F |
-            // Eventhough this sum is unbounded, it grows very slowly, so full compression will
-            // rarely be called
-            max_samples: 5 * expected_least_compressed_samples,
-            max_expected_error,
-            len: 0,
+impl SummaryBuilder {
+    /// The default maximum error
+    pub const DEFAULT_MAX_EXPECTED_ERROR: f64 = 0.01;
+
+    /// The default number of checkpoints to keep, as a multiple of `1/max_expected_error`.
+    ///
+    /// This encodes a tradeoff between using more memory and compressing more frequently.
+    /// However, with the implemented micro-compression at every insert, in the worst case
+    /// (sorted stream of values), the structure will accumulate all of the `F=1/eps` first
+    /// elements, then half of the next `F/2`, then a third of the next `F/2`, and so on.
+    /// This means that in the worst case we'll reach:
+    ///
+    /// | saved samples | saw samples |
+    /// |---------------|-------------|
+    /// |        1.00 F |           F |
+    /// |        2.01 F |         6 F |
+    /// |        3.00 F |        42 F |
+    /// |        4.00 F |       309 F |
+    /// |        5.00 F |      2276 F |
+    ///
+    /// Eventhough this sum is unbounded, it grows very slowly, so full compression will rarely be
+    /// called
+    pub const DEFAULT_MAX_SAMPLES_FACTOR: u64 = 5;
+
+    /// Create a new builder with the default parameters
+    pub fn new() -> Self {
+        SummaryBuilder {
+            max_expected_error: Self::DEFAULT_MAX_EXPECTED_ERROR,
+            max_samples_factor: Self::DEFAULT_MAX_SAMPLES_FACTOR,
         }
     }
 
-    /// Insert a single new value into the Summary
-    pub fn insert_one(&mut self, value: T) {
-        self.len += 1;
-        let cap = self.max_g_delta();
+    /// Set the maximum error of the answers, as a fraction of the number of inserted samples
+    pub fn max_expected_error(mut self, max_expected_error: f64) -> Self {
+        self.max_expected_error = max_expected_error;
+        self
+    }
 
-        self.samples_tree.push_value(value, cap);
+    /// Set how many checkpoints are kept before a full compression, as a multiple of
+    /// `1/max_expected_error`. Smaller values use less memory but compress more frequently
+    pub fn max_samples_factor(mut self, max_samples_factor: u64) -> Self {
+        self.max_samples_factor = max_samples_factor;
+        self
+    }
 
-        // Keep the number of saved samples bounded
-        if self.samples_tree.len() > self.max_samples as usize {
-            self.compress();
+    /// Create a new empty summary with the configured parameters
+    pub fn build<S, const CAP: usize>(self) -> SummaryWithCapacity<S, CAP> {
+        SummaryWithCapacity {
+            samples_tree: SamplesTree::new(),
+            max_samples: max_samples(self.max_expected_error, self.max_samples_factor),
+            max_samples_factor: self.max_samples_factor,
+            max_expected_error: self.max_expected_error,
+            len: 0,
         }
     }
+}
 
-    /// Merge another Summary into this one
-    pub fn merge(&mut self, other: Summary<T>) {
-        assert!(
-            other.max_expected_error <= self.max_expected_error,
-            "The incoming Summary must have an equal or smaller max_expected_error"
-        );
-        self.merge_sorted_samples(other.samples_tree.into_iter(), other.len);
+impl Default for SummaryBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Return the maximum number of checkpoints to keep for the given parameters
+fn max_samples(max_expected_error: f64, max_samples_factor: u64) -> usize {
+    let expected_least_compressed_samples = crate::ceil(1. / max_expected_error);
+    (max_samples_factor * expected_least_compressed_samples) as usize
+}
+
+/// A `Summary` whose internal tree stores at most `CAP` checkpoints per node.
+///
+/// `CAP` must be even: other values are refused at compile time
+#[derive(Debug)]
+pub struct SummaryWithCapacity<S, const CAP: usize> {
+    samples_tree: SamplesTree<S, CAP>,
+    /// Maximum number of checkpoints to keep
+    max_samples: usize,
+    /// `max_samples` as a multiple of `1/max_expected_error`
+    max_samples_factor: u64,
+    /// Maximum error
+    max_expected_error: f64,
+    /// Number of samples already seen
+    len: u64,
+}
+
+impl<S, const CAP: usize> SummaryWithCapacity<S, CAP> {
+    /// Create a new empty Summary. See `SummaryBuilder` to tune the other parameters
+    pub fn new(max_expected_error: f64) -> Self {
+        SummaryBuilder::new()
+            .max_expected_error(max_expected_error)
+            .build()
     }
 
     /// Query for a desired quantile
     /// Return None if and only if the summary is empty
-    pub fn query(&self, q: f64) -> Option<&T> {
-        self.query_with_error(q).map(|(value, _error)| value)
+    pub fn query(&self, quantile: f64) -> Option<&S> {
+        self.samples_tree.query(quantile, self.len)
     }
 
-    /// Query for a desired quantile and return the query maximum error
+    /// Query for a desired quantile, also returning the greatest possible error of the answer,
+    /// as a fraction of the number of inserted samples. It never exceeds `max_expected_error`.
     /// Return None if and only if the summary is empty
-    pub fn query_with_error(&self, quantile: f64) -> Option<(&T, f64)> {
-        // Find the sample with the smallest maximum rank error
-
-        let target_rank = quantile_to_rank(quantile, self.len);
-        let mut min_rank = 0;
-
+    pub fn query_with_error(&self, quantile: f64) -> Option<(&S, f64)> {
         self.samples_tree
-            .iter()
-            // For each sample, calculate the maximum rank error if we choose it as the answer
-            .map(|sample| {
-                // This sample's rank is in [min_rank, max_rank] (inclusive in both sides)
-                min_rank += sample.g;
-                let max_rank = min_rank + sample.delta;
-                let mid_rank = (min_rank + max_rank) / 2;
-
-                // In the worst case, the correct sample's rank is at the opposite extremity
-                let max_rank_error = if target_rank > mid_rank {
-                    target_rank - min_rank
-                } else {
-                    max_rank - target_rank
-                };
-
-                (sample, max_rank_error)
-            })
-            // Grab the best answer
-            .min_by_key(|&(_sample, max_rank_error)| max_rank_error)
-            // Output values consistent with the public API (the value and quantile error)
-            .map(|(sample, rank_error)| (&sample.value, rank_error as f64 / self.len as f64))
+            .query_with_error(quantile, self.len)
+            .map(|(sample, max_rank_error)| (sample, max_rank_error as f64 / self.len as f64))
+    }
+
+    /// Query for the minimum, the maximum and the usual percentiles at once, with a single walk
+    /// over the stored samples. Each field is equal to the answer of the corresponding `query()`.
+    /// Return None if and only if the summary is empty
+    pub fn stats(&self) -> Option<SummaryStats<&S>> {
+        let [min, p50, p90, p95, p99, max] = self
+            .samples_tree
+            .query_many([0., 0.5, 0.9, 0.95, 0.99, 1.], self.len)?
+            .map(|(sample, _max_rank_error)| sample);
+        Some(SummaryStats {
+            min,
+            p50,
+            p90,
+            p95,
+            p99,
+            max,
+        })
     }
 
     /// Get the maximum desired error
@@ -107,180 +189,308 @@ impl<T: Ord> Summary<T> {
         self.max_expected_error
     }
 
-    /// Get the number of inserted values
+    /// Get the number of inserted samples
     pub fn len(&self) -> u64 {
         self.len
     }
 
-    /// Get the current limit on g+delta
+    /// Return whether no sample was inserted yet
+    pub fn is_empty(&self) -> bool {
+        self.samples_tree.is_empty()
+    }
+
+    /// Get the number of checkpoints currently stored to represent the inserted samples
+    pub fn num_checkpoints(&self) -> usize {
+        self.samples_tree.len()
+    }
+
+    /// Iterate over the stored samples in ascending order, along with their `g` and `delta` as
+    /// defined by Greenwald and Khanna.
+    ///
+    /// `g` is the least number of inserted samples between the preceding stored sample and this
+    /// one, so that summing them gives the least possible rank of each sample. `delta` is how much
+    /// greater its rank can be.
+    pub fn samples(&self) -> impl Iterator<Item = (&S, u64, u64)> {
+        self.samples_tree.iter().map(|checkpoint| {
+            (
+                checkpoint.sample(),
+                checkpoint.min_gap(),
+                checkpoint.max_gap() - checkpoint.min_gap(),
+            )
+        })
+    }
+
+    /// Get the current limit on the checkpoints' `max_gap`
     /// An invariant of this structure is that:
-    /// max(sample.g + sample.delta) <= max_g_delta, for all intermediate samples
-    fn max_g_delta(&self) -> u64 {
-        return (2. * self.max_expected_error * self.len as f64).floor() as u64;
+    /// max(checkpoint.max_gap) <= maximal_gap, for all checkpoints
+    fn maximal_gap(&self) -> u64 {
+        // Truncation is the floor for non-negative values
+        (2. * self.max_expected_error * self.len as f64) as u64
+    }
+}
+
+impl<S: Ord, const CAP: usize> SummaryWithCapacity<S, CAP> {
+    /// Insert a single new sample into the Summary
+    pub fn insert_one(&mut self, sample: S) {
+        self.len += 1;
+        let maximal_gap = self.maximal_gap();
+        self.samples_tree.record_sample(sample, maximal_gap);
+
+        // Keep the number of checkpoints bounded
+        if self.samples_tree.len() > self.max_samples {
+            self.compress();
+        }
+    }
+
+    /// Merge another Summary into this one. The resulting `max_expected_error` is the greatest of
+    /// both summaries
+    pub fn merge(&mut self, other: Self) {
+        self.max_expected_error = self.max_expected_error.max(other.max_expected_error);
+        self.max_samples = max_samples(self.max_expected_error, self.max_samples_factor);
+        self.merge_sorted_checkpoints(other.samples_tree.into_iter(), other.len);
     }
 
-    /// Compress the samples: search for samples to "forget"
+    /// Compress the checkpoints: search for checkpoints to "forget"
     fn compress(&mut self) {
-        let mut compressor = SamplesCompressor::new(self.max_g_delta());
+        let mut compressor = SamplesCompressor::new(self.maximal_gap());
 
-        // Consume the samples (since T may not implement Copy, we temporally place a zero tree)
+        // Consume the checkpoints (since S may not implement Copy, we temporally place an empty
+        // tree)
         let old_samples_tree = mem::replace(&mut self.samples_tree, SamplesTree::new());
-        for sample in old_samples_tree.into_iter() {
-            compressor.push(sample);
+        for checkpoint in old_samples_tree {
+            compressor.push(checkpoint);
         }
 
         self.samples_tree = compressor.into_samples_tree();
     }
 
-    /// Merge a source of sorted samples into this Summary
-    /// `other_len` is the number of values represented by the samples, that is, the sum of all its `g` values
-    /// `other_capacity` is the minimum capacity for the final merged samples vector
-    pub(super) fn merge_sorted_samples<I>(&mut self, other_samples: I, other_len: u64)
+    /// Merge a source of sorted checkpoints into this Summary
+    /// `other_len` is the number of samples represented by the checkpoints, that is, the sum of
+    /// all their `min_gap` values
+    fn merge_sorted_checkpoints<I>(&mut self, other_checkpoints: I, other_len: u64)
     where
-        I: Iterator<Item = Sample<T>>,
+        I: Iterator<Item = Checkpoint<S>>,
     {
         // Create a streaming compressor
-        // Note the use of the largest capacity to avoid reallocs in final vector
         self.len += other_len;
-        let max_g_delta = self.max_g_delta();
-        let mut compressor = SamplesCompressor::new(max_g_delta);
+        let mut compressor = SamplesCompressor::new(self.maximal_gap());
 
-        // Get current samples as iterator
+        // Get current checkpoints as iterator
         let old_samples_tree = mem::replace(&mut self.samples_tree, SamplesTree::new());
-        let self_samples = old_samples_tree.into_iter();
+        let self_checkpoints = old_samples_tree.into_iter();
 
         // Prepare state for merge
-        let mut other_input = IncomingMergeState::new(other_samples);
-        let mut self_input = IncomingMergeState::new(self_samples);
+        let mut other_input = IncomingMergeState::new(other_checkpoints);
+        let mut self_input = IncomingMergeState::new(self_checkpoints);
 
         // Bring the least from each iterator until one of them ends
         loop {
             match (self_input.peek(), other_input.peek()) {
-                // Nothing to merge from one of the sides: move remaining values
+                // Nothing to merge from one of the sides: move remaining checkpoints
                 (None, _) => {
                     other_input.push_remaining_to(&mut compressor);
-                    self.samples_tree = compressor.into_samples_tree();
                     break;
                 }
                 (_, None) => {
                     self_input.push_remaining_to(&mut compressor);
-                    self.samples_tree = compressor.into_samples_tree();
                     break;
                 }
                 (Some(self_peeked), Some(other_peeked)) => {
-                    // Detect from which input to consume next and prepare the next sample
-                    let mut new_sample;
-                    if self_peeked.value < other_peeked.value {
-                        new_sample = self_input.pop_front();
-                        new_sample.delta += other_input.aditional_delta();
+                    // Detect from which input to consume next and prepare the next checkpoint
+                    let mut new_checkpoint;
+                    if self_peeked < other_peeked {
+                        new_checkpoint = self_input.pop_front();
+                        new_checkpoint.widen(other_input.additional_gap());
                     } else {
-                        new_sample = other_input.pop_front();
-                        new_sample.delta += self_input.aditional_delta();
+                        new_checkpoint = other_input.pop_front();
+                        new_checkpoint.widen(self_input.additional_gap());
                     };
 
-                    compressor.push(new_sample);
+                    compressor.push(new_checkpoint);
                 }
             }
         }
+
+        self.samples_tree = compressor.into_samples_tree();
     }
+}
 
-    #[cfg(test)]
-    pub(super) fn samples_spec(&self) -> Vec<(T, u64, u64)>
-    where
-        T: Copy,
-    {
-        self.samples_tree
-            .iter()
-            .map(|&sample| (sample.value, sample.g, sample.delta))
-            .collect::<Vec<_>>()
+impl<S: Copy + Into<f64>, const CAP: usize> SummaryWithCapacity<S, CAP> {
+    /// Query for a desired quantile, linearly interpolating between the values of the two stored
+    /// samples around it, instead of answering with one of them.
+    ///
+    /// Each sample is placed at the middle of its possible ranks and the quantile is mapped to
+    /// the rank `1 + quantile * (len - 1)`, so that `0` and `1` give the minimum and the maximum.
+    /// Return None if and only if the summary is empty
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::Summary;
+    /// let mut summary = Summary::new(0.01);
+    /// for &value in &[0, 4, 9] {
+    ///     summary.insert_one(value);
+    /// }
+    /// assert_eq!(summary.query(0.75), Some(&9));
+    /// assert_eq!(summary.query_interpolated(0.75), Some(6.5));
+    /// ```
+    ///
+    /// # Panics
+    /// This call will panic if `quantile` is out of range
+    pub fn query_interpolated(&self, quantile: f64) -> Option<f64> {
+        assert!(
+            (0. ..=1.).contains(&quantile),
+            "Invalid quantile {}: out of range",
+            quantile
+        );
+        let target_rank = 1. + quantile * self.len.saturating_sub(1) as f64;
+
+        let mut min_rank = 0;
+        let mut previous: Option<(f64, f64)> = None;
+        for (&sample, g, delta) in self.samples() {
+            min_rank += g;
+            let rank = min_rank as f64 + delta as f64 / 2.;
+            let value = sample.into();
+            if rank >= target_rank {
+                return Some(match previous {
+                    Some((previous_rank, previous_value)) => {
+                        let ratio = (target_rank - previous_rank) / (rank - previous_rank);
+                        previous_value + ratio * (value - previous_value)
+                    }
+                    None => value,
+                });
+            }
+            previous = Some((rank, value));
+        }
+
+        previous.map(|(_rank, value)| value)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use rand::prelude::*;
-    use rand_pcg::Pcg64;
+    use crate::rank_to_quantile;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    impl<S: Copy, const CAP: usize> SummaryWithCapacity<S, CAP> {
+        fn checkpoints_spec(&self) -> Vec<(S, u64, u64)> {
+            self.samples_tree
+                .iter()
+                .map(|checkpoint| {
+                    (
+                        *checkpoint.sample(),
+                        checkpoint.min_gap(),
+                        checkpoint.max_gap(),
+                    )
+                })
+                .collect()
+        }
+    }
 
     #[test]
     fn insert_one_by_one_and_query() {
         // insert [8, 6, 0, 4, 3, 9, 2, 5, 1, 7] one by one
         let mut summary = Summary::new(0.2);
+        assert!(summary.is_empty());
+        assert_eq!(summary.query(0.5), None);
 
         // First
         summary.insert_one(8);
-        assert_eq!(summary.samples_spec(), vec![(8, 1, 0)]);
+        assert_eq!(summary.checkpoints_spec(), vec![(8, 1, 1)]);
 
         // New minimum
         summary.insert_one(6);
-        assert_eq!(summary.samples_spec(), vec![(6, 1, 0), (8, 1, 0)]);
+        assert_eq!(summary.checkpoints_spec(), vec![(6, 1, 1), (8, 1, 1)]);
 
         // New minimum
         summary.insert_one(0);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (6, 1, 0), (8, 1, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (6, 1, 1), (8, 1, 1)],
         );
 
         //
         summary.insert_one(4);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (4, 1, 0), (6, 1, 0), (8, 1, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (4, 1, 1), (6, 1, 1), (8, 1, 1)],
         );
 
-        // Local compression (cap=2)
+        // Local compression (maximal_gap=2)
         summary.insert_one(3);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (4, 2, 0), (6, 1, 0), (8, 1, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (4, 2, 2), (6, 1, 1), (8, 1, 1)],
         );
 
-        // New maximum + local compression (cap=2)
+        // New maximum + local compression (maximal_gap=2)
         summary.insert_one(9);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (4, 2, 0), (6, 1, 0), (9, 2, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (4, 2, 2), (6, 1, 1), (9, 2, 2)],
         );
 
         //
         summary.insert_one(2);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (2, 1, 1), (4, 2, 0), (6, 1, 0), (9, 2, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (2, 1, 2), (4, 2, 2), (6, 1, 1), (9, 2, 2)],
         );
 
-        // Local compression (cap=3)
+        // Local compression (maximal_gap=3)
         summary.insert_one(5);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (2, 1, 1), (4, 2, 0), (6, 2, 0), (9, 2, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (2, 1, 2), (4, 2, 2), (6, 2, 2), (9, 2, 2)],
         );
 
-        // Local compression (cap=3)
+        // Local compression (maximal_gap=3)
         summary.insert_one(1);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (2, 2, 1), (4, 2, 0), (6, 2, 0), (9, 2, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (2, 2, 3), (4, 2, 2), (6, 2, 2), (9, 2, 2)],
         );
 
-        // Local compression (cap=4)
+        // Local compression (maximal_gap=4)
         summary.insert_one(7);
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (2, 2, 1), (4, 2, 0), (6, 2, 0), (9, 3, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (2, 2, 3), (4, 2, 2), (6, 2, 2), (9, 3, 3)],
         );
+        assert_eq!(summary.len(), 10);
+        assert_eq!(summary.num_checkpoints(), 5);
 
-        // Compression (cap=4)
+        // Query all ranks
+        let check_rank = |rank, expected_value| {
+            let q = rank_to_quantile(rank, summary.len());
+            assert_eq!(summary.query(q), Some(&expected_value));
+        };
+        check_rank(1, 0);
+        check_rank(2, 0);
+        check_rank(3, 2);
+        check_rank(4, 2);
+        check_rank(5, 4);
+        check_rank(6, 4);
+        check_rank(7, 6);
+        check_rank(8, 6);
+        check_rank(9, 9);
+        check_rank(10, 9);
+
+        // Compression (maximal_gap=4)
         summary.compress();
         assert_eq!(
-            summary.samples_spec(),
-            vec![(0, 1, 0), (4, 4, 0), (6, 2, 0), (9, 3, 0)],
+            summary.checkpoints_spec(),
+            vec![(0, 1, 1), (4, 4, 4), (6, 2, 2), (9, 3, 3)],
         );
+        assert_eq!(summary.len(), 10);
+        assert_eq!(summary.num_checkpoints(), 4);
 
         // Query all ranks
         let check_rank = |rank, expected_value, rank_error| {
-            let q = crate::rank_to_quantile(rank, summary.len());
+            let q = rank_to_quantile(rank, summary.len());
             let (&value, error) = summary.query_with_error(q).unwrap();
             assert_eq!(expected_value, value);
             assert_eq!(rank_error as f64 / summary.len() as f64, error);
@@ -297,29 +507,38 @@ mod test {
         check_rank(10, 9, 0);
     }
 
+    /// Insert all samples into a summary with a 10% error, returning how many full compressions
+    /// happened, the number of samples and the final number of checkpoints
+    fn count_compressions<I: Iterator<Item = u64>>(iter: I) -> (u64, u64, usize) {
+        count_compressions_with(Summary::new(0.1), iter)
+    }
+
+    /// Like `count_compressions()`, but filling the given summary
+    fn count_compressions_with<I: Iterator<Item = u64>>(
+        mut summary: Summary<u64>,
+        iter: I,
+    ) -> (u64, u64, usize) {
+        let mut num_compressions = 0;
+
+        let mut prev_num_checkpoints = 0;
+        for i in iter {
+            summary.insert_one(i);
+            let num_checkpoints = summary.num_checkpoints();
+            if num_checkpoints < prev_num_checkpoints {
+                num_compressions += 1;
+            }
+            prev_num_checkpoints = num_checkpoints;
+        }
+
+        (num_compressions, summary.len(), summary.num_checkpoints())
+    }
+
     #[test]
     fn compression() {
-        // Local compression should reduce a lot the number of saved samples
+        // Local compression should reduce a lot the number of saved checkpoints
         // For 1 million samples, with a 10% error, a full compression will only
         // kick in once
 
-        fn count_compressions<I: Iterator<Item = usize>>(iter: I) -> (u64, u64, usize) {
-            let mut num_compressions = 0;
-            let mut summary = Summary::new(0.1);
-
-            let mut prev_samples_len = 0;
-            for i in iter {
-                summary.insert_one(i);
-                let samples_len = summary.samples_tree.len();
-                if samples_len < prev_samples_len {
-                    num_compressions += 1;
-                }
-                prev_samples_len = samples_len;
-            }
-
-            (num_compressions, summary.len, summary.samples_tree.len())
-        };
-
         // Ascending and descending are both worst case and identical
         assert_eq!(count_compressions(0..1_000), (0, 1_000, 31));
         assert_eq!(count_compressions(0..10_000), (0, 10_000, 41));
@@ -331,10 +550,212 @@ mod test {
         assert_eq!(count_compressions((0..100_000).rev()), (1, 100_000, 9));
         assert_eq!(count_compressions((0..1_000_000).rev()), (1, 1_000_000, 19));
 
-        // Random is much better
-        let mut values = (0..1_000_000).collect::<Vec<_>>();
-        let mut rng = Pcg64::seed_from_u64(17);
-        values.shuffle(&mut rng);
-        assert_eq!(count_compressions(values.into_iter()), (0, 1_000_000, 13));
+        // Scrambled is much better. 7919 is a prime that does not divide `num`, so this visits
+        // every value
+        let scrambled = |num| (0..num).map(move |i| i * 7919 % num);
+        assert_eq!(count_compressions(scrambled(1_000)), (0, 1_000, 12));
+        assert_eq!(count_compressions(scrambled(1_000_000)), (0, 1_000_000, 22));
+    }
+
+    #[test]
+    fn compress_tiny() {
+        // Compressing less than three checkpoints has nothing to forget
+        for num in 0..3 {
+            let mut summary = Summary::new(0.5);
+            for sample in 0..num {
+                summary.insert_one(sample);
+            }
+            let checkpoints = summary.checkpoints_spec();
+            let (min, max) = (summary.query(0.).copied(), summary.query(1.).copied());
+
+            summary.compress();
+            assert_eq!(summary.checkpoints_spec(), checkpoints);
+            assert_eq!(summary.len(), num);
+            assert_eq!(summary.query(0.).copied(), min);
+            assert_eq!(summary.query(1.).copied(), max);
+        }
+    }
+
+    #[test]
+    fn max_samples_factor() {
+        // A smaller factor trades memory for more frequent full compressions
+        let build = |max_samples_factor| {
+            SummaryBuilder::new()
+                .max_expected_error(0.1)
+                .max_samples_factor(max_samples_factor)
+                .build()
+        };
+        let (default_compressions, _, _) = count_compressions(0..100_000);
+        let (compressions, len, _) = count_compressions_with(build(2), 0..100_000);
+        assert_eq!(len, 100_000);
+        assert!(
+            compressions > default_compressions,
+            "{} <= {}",
+            compressions,
+            default_compressions
+        );
+
+        // The default factor is the one used by `new()`
+        let default = count_compressions_with(build(5), 0..100_000);
+        assert_eq!(default, count_compressions(0..100_000));
+    }
+
+    #[test]
+    fn merge() {
+        // Split a scrambled permutation of `0..num`, so that the rank of each sample is
+        // `sample + 1`
+        let num = 10_000;
+        let epsilon = 0.01;
+        let mut summary = Summary::new(epsilon);
+        let mut other = Summary::new(epsilon);
+        for i in 0..num {
+            let sample = i * 7919 % num;
+            if sample % 3 == 0 {
+                summary.insert_one(sample);
+            } else {
+                other.insert_one(sample);
+            }
+        }
+
+        summary.merge(other);
+        assert_eq!(summary.len(), num);
+        assert_eq!(summary.query(0.), Some(&0));
+        assert_eq!(summary.query(1.), Some(&(num - 1)));
+        for rank in 1..=num {
+            let quantile = rank_to_quantile(rank, num);
+            let &sample = summary.query(quantile).unwrap();
+            let error = ((sample + 1) as f64 - rank as f64).abs() / num as f64;
+            assert!(error <= epsilon, "rank={}, sample={}", rank, sample);
+        }
+
+        // Merging an empty summary changes nothing
+        let num_checkpoints = summary.num_checkpoints();
+        summary.merge(Summary::new(epsilon));
+        assert_eq!(summary.len(), num);
+        assert_eq!(summary.num_checkpoints(), num_checkpoints);
+    }
+
+    #[test]
+    fn merge_less_precise() {
+        // Split a scrambled permutation of `0..num`, so that the rank of each sample is
+        // `sample + 1`
+        let num = 10_000;
+        let mut summary = Summary::new(0.1);
+        let mut other = Summary::new(0.2);
+        for i in 0..num {
+            let sample = i * 7919 % num;
+            if sample % 2 == 0 {
+                summary.insert_one(sample);
+            } else {
+                other.insert_one(sample);
+            }
+        }
+
+        // The error bound relaxes to the least precise summary
+        summary.merge(other);
+        assert_eq!(summary.max_expected_error(), 0.2);
+        assert_eq!(summary.len(), num);
+        for rank in 1..=num {
+            let quantile = rank_to_quantile(rank, num);
+            let (&sample, max_error) = summary.query_with_error(quantile).unwrap();
+            let error = ((sample + 1) as f64 - rank as f64).abs() / num as f64;
+            assert!(error <= max_error, "rank={}, sample={}", rank, sample);
+            assert!(max_error <= 0.2, "rank={}, max_error={}", rank, max_error);
+        }
+    }
+
+    #[test]
+    fn samples() {
+        let mut summary = Summary::new(0.2);
+        assert_eq!(summary.samples().count(), 0);
+        for &value in &[8, 6, 0, 4, 3, 9, 2, 5, 1, 7] {
+            summary.insert_one(value);
+        }
+
+        // Reconstruct the rank range of each sample
+        let mut min_rank = 0;
+        let ranks: Vec<_> = summary
+            .samples()
+            .map(|(&sample, g, delta)| {
+                min_rank += g;
+                (sample, min_rank, min_rank + delta)
+            })
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![(0, 1, 1), (2, 3, 4), (4, 5, 5), (6, 7, 7), (9, 10, 10)]
+        );
+        assert_eq!(min_rank, summary.len());
+
+        // The true rank of each sample is `sample + 1`
+        for (sample, min_rank, max_rank) in ranks {
+            let rank = sample + 1;
+            assert!(min_rank <= rank && rank <= max_rank);
+        }
+    }
+
+    #[test]
+    fn query_interpolated() {
+        let mut summary = Summary::new(0.01);
+        assert_eq!(summary.query_interpolated(0.5), None);
+
+        summary.insert_one(4);
+        assert_eq!(summary.query_interpolated(0.), Some(4.));
+        assert_eq!(summary.query_interpolated(1.), Some(4.));
+
+        summary.insert_one(9);
+        let between = summary.query_interpolated(0.5).unwrap();
+        assert!(4. < between && between < 9., "{}", between);
+        assert_eq!(summary.query_interpolated(0.), Some(4.));
+        assert_eq!(summary.query_interpolated(1.), Some(9.));
+
+        // The answers grow with the quantile, even across compressed samples
+        let mut summary = Summary::new(0.2);
+        for &value in &[8, 6, 0, 4, 3, 9, 2, 5, 1, 7] {
+            summary.insert_one(value);
+        }
+        let answers: Vec<_> = (0..=100)
+            .map(|i| summary.query_interpolated(i as f64 / 100.).unwrap())
+            .collect();
+        assert!(answers.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(answers[0], 0.);
+        assert_eq!(answers[100], 9.);
+    }
+
+    #[test]
+    fn stats() {
+        let mut summary = Summary::new(0.01);
+        assert_eq!(summary.stats(), None);
+
+        // Every value is inserted several times, in a scrambled order
+        for i in 0..10_000 {
+            summary.insert_one(i * 7919 % 1000);
+        }
+
+        let stats = summary.stats().unwrap();
+        assert_eq!(Some(stats.min), summary.query(0.));
+        assert_eq!(Some(stats.p50), summary.query(0.5));
+        assert_eq!(Some(stats.p90), summary.query(0.9));
+        assert_eq!(Some(stats.p95), summary.query(0.95));
+        assert_eq!(Some(stats.p99), summary.query(0.99));
+        assert_eq!(Some(stats.max), summary.query(1.));
+        assert_eq!((*stats.min, *stats.max), (0, 999));
+    }
+
+    #[test]
+    fn non_clone_samples() {
+        // A sample type that can only be moved
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(String);
+
+        // Every insert is a new global minimum
+        let mut summary = Summary::new(0.1);
+        for i in (0..1000).rev() {
+            summary.insert_one(Key(format!("{:04}", i)));
+        }
+
+        assert_eq!(summary.len(), 1000);
+        assert_eq!(summary.query(0.), Some(&Key("0000".to_string())));
+        assert_eq!(summary.query(1.), Some(&Key("0999".to_string())));
     }
 }
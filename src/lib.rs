@@ -1,3 +1,17 @@
+//! # `no_std` support
+//! The summary structures only need `core` and `alloc`. Disabling the default `std` feature
+//! builds the crate without `std`:
+//!
+//! ```text
+//! cargo build --no-default-features
+//! ```
+//!
+//! The `quantile-generator` module depends on `std`, so enabling it also enables `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod algorithm;
 pub use algorithm::*;
 
@@ -36,11 +50,11 @@ pub mod quantile_generator;
 /// This call will panic if `quantile` is out of range
 pub fn quantile_to_rank(quantile: f64, num: u64) -> u64 {
     assert!(
-        quantile >= 0. && quantile <= 1.,
+        (0. ..=1.).contains(&quantile),
         "Invalid quantile {}: out of range",
         quantile
     );
-    ((quantile * num as f64).ceil() as u64).max(1)
+    ceil(quantile * num as f64).max(1)
 }
 
 /// Convert from rank to the quantile, where `0 <= quantile <= 1` and `1 <= rank <= num`.
@@ -78,6 +92,19 @@ pub fn rank_to_quantile(rank: u64, num: u64) -> f64 {
     }
 }
 
+/// Round a non-negative float up to the next integer.
+///
+/// `f64::ceil` is not available in `core`, so this is implemented with casts, which are exact
+/// for the range of values used by the rank helpers.
+fn ceil(x: f64) -> u64 {
+    let truncated = x as u64;
+    if (truncated as f64) < x {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
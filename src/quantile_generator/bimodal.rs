@@ -0,0 +1,111 @@
+use super::{PinnedDistribution, PinnedGenerator};
+use rand::Rng;
+use rand_pcg::Pcg64;
+
+/// An iterator that will generate values from a mixture of two clusters
+///
+/// Each cluster spreads uniformly over `(center-1, center+1)` and `weight` is the probability of
+/// the first one. Every value is drawn from this mixture restricted to the side of `x` that keeps
+/// `x` at the desired quantile. If the mixture has no values on that side, they are drawn from
+/// `(x-1, x)` or `[x, x+1)` instead. The values are returned in a random order.
+pub type BimodalGenerator = PinnedGenerator<BimodalDistribution>;
+
+/// The values drawn by a `BimodalGenerator`
+#[derive(Debug, Clone)]
+pub struct BimodalDistribution {
+    centers: (f64, f64),
+    weight: f64,
+}
+
+impl BimodalGenerator {
+    /// Create a new iterator with the given parameters
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// let it = BimodalGenerator::new(0.5, 17., 1001, 22, (0., 100.), 0.5);
+    /// let mut values: Vec<_> = it.map(|f| f.into_inner()).collect();
+    /// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(values[500], 17.);
+    /// assert!(values[499] < 1. && values[501] > 99.);
+    /// ```
+    pub fn new(
+        quantile: f64,
+        value: f64,
+        num: usize,
+        seed: u64,
+        centers: (f64, f64),
+        weight: f64,
+    ) -> BimodalGenerator {
+        assert!((0. ..=1.).contains(&weight));
+        let distribution = BimodalDistribution { centers, weight };
+        PinnedGenerator::with_distribution(quantile, value, num, seed, distribution)
+    }
+}
+
+impl BimodalDistribution {
+    /// Draw from the mixture restricted to `[low, high)`, if it has any value there
+    fn next_in_range(&self, low: f64, high: f64, rng: &mut Pcg64) -> Option<f64> {
+        // The part of each cluster inside the range and its probability mass
+        let clusters = [
+            (self.centers.0, self.weight),
+            (self.centers.1, 1. - self.weight),
+        ];
+        let parts = clusters.map(|(center, weight)| {
+            let start = (center - 1.).max(low);
+            let end = (center + 1.).min(high);
+            (start, end, weight * (end - start).max(0.))
+        });
+
+        let total_mass = parts[0].2 + parts[1].2;
+        if total_mass == 0. {
+            return None;
+        }
+
+        let (start, end, _) = if rng.gen::<f64>() * total_mass < parts[0].2 {
+            parts[0]
+        } else {
+            parts[1]
+        };
+        Some(start + rng.gen::<f64>() * (end - start))
+    }
+}
+
+impl PinnedDistribution for BimodalDistribution {
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        match self.next_in_range(f64::NEG_INFINITY, value, rng) {
+            Some(lesser) => lesser,
+            None => value - rng.gen::<f64>(),
+        }
+    }
+
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        match self.next_in_range(value, f64::INFINITY, rng) {
+            Some(greater) => greater,
+            None => value + rng.gen::<f64>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ordered_float::NotNan;
+
+    #[test]
+    fn two_modes() {
+        // The target value is the center of the second cluster, which is the quantile
+        // 0.3 + 0.7 / 2 = 0.65 of the mixture, so values follow the mixture exactly
+        let values: Vec<_> = BimodalGenerator::new(0.65, 100., 10_000, 17, (0., 100.), 0.3)
+            .map(NotNan::into_inner)
+            .collect();
+
+        let first_mode = values.iter().filter(|&&v| (-1. ..1.).contains(&v)).count();
+        let second_mode = values
+            .iter()
+            .filter(|&&v| (99. ..101.).contains(&v))
+            .count();
+        assert_eq!(first_mode + second_mode, values.len());
+        assert!(first_mode > 2500 && first_mode < 3500, "{}", first_mode);
+    }
+}
@@ -0,0 +1,91 @@
+use super::{PinnedDistribution, PinnedGenerator};
+use crate::quantile_to_rank;
+use rand::Rng;
+use rand_pcg::Pcg64;
+
+/// An iterator that will generate many repeated values
+///
+/// The values are drawn randomly from a set of `k` distinct levels, spaced by one unit, and
+/// returned in a random order. `x` is one of the levels: the lesser values are drawn from the
+/// levels below it and the others from `x` and the levels above it.
+pub type DuplicateGenerator = PinnedGenerator<DuplicateDistribution>;
+
+/// The values drawn by a `DuplicateGenerator`
+#[derive(Debug, Clone)]
+pub struct DuplicateDistribution {
+    // Number of distinct levels strictly smaller than `value`
+    lesser_levels: usize,
+    // Number of distinct levels greater or equal to `value`
+    greater_levels: usize,
+}
+
+impl DuplicateGenerator {
+    /// Create a new iterator with the given parameters, where `k` is the number of distinct
+    /// levels, that must be at least 2
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// use ordered_float::NotNan;
+    /// let it = DuplicateGenerator::new(0.5, 17., 1000, 22, 3);
+    /// let mut values: Vec<_> = it.collect();
+    /// values.sort();
+    /// values.dedup();
+    /// assert_eq!(values, vec![NotNan::from(16.), NotNan::from(17.), NotNan::from(18.)]);
+    /// ```
+    pub fn new(quantile: f64, value: f64, num: usize, seed: u64, k: usize) -> DuplicateGenerator {
+        assert!(num > 0);
+        assert!(k >= 2);
+        let rank = quantile_to_rank(quantile, num as u64) as usize;
+        let remaining_lesser = rank - 1;
+
+        // Split the levels around the target value, making sure each side has at least one level
+        // when it has values to draw
+        let lesser_levels = if remaining_lesser == 0 {
+            0
+        } else if remaining_lesser == num - 1 {
+            k - 1
+        } else {
+            ((k - 1) / 2).max(1)
+        };
+
+        let distribution = DuplicateDistribution {
+            lesser_levels,
+            greater_levels: k - lesser_levels,
+        };
+        PinnedGenerator::with_distribution(quantile, value, num, seed, distribution)
+    }
+}
+
+impl PinnedDistribution for DuplicateDistribution {
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        let level = rng.gen_range(1, self.lesser_levels + 1);
+        value - level as f64
+    }
+
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        let level = rng.gen_range(0, self.greater_levels);
+        value + level as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distinct_levels() {
+        fn check(quantile: f64, k: usize) {
+            let mut values: Vec<_> = DuplicateGenerator::new(quantile, 17., 1000, 17, k).collect();
+            values.sort();
+            values.dedup();
+            assert_eq!(values.len(), k, "quantile={}, k={}", quantile, k);
+        }
+
+        for &quantile in &[0., 0.5, 1.] {
+            for &k in &[2, 3, 5, 10] {
+                check(quantile, k);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{PinnedDistribution, PinnedGenerator};
+use crate::quantile_to_rank;
+use rand::Rng;
+use rand_pcg::Pcg64;
+
+/// An iterator that will generate exponentially-distributed random values
+///
+/// The values follow an exponential distribution with rate `lambda`, shifted so that `x` sits at
+/// the desired quantile, and are returned in a random order. The lesser values are drawn from the
+/// head of the distribution and the greater ones from its (heavy) tail.
+pub type ExponentialGenerator = PinnedGenerator<ExponentialDistribution>;
+
+/// The values drawn by an `ExponentialGenerator`
+#[derive(Debug, Clone)]
+pub struct ExponentialDistribution {
+    lambda: f64,
+    // The fraction of values strictly smaller than `value`
+    lesser_ratio: f64,
+    // The distance from the least possible value to `value`
+    lesser_span: f64,
+}
+
+impl ExponentialGenerator {
+    /// Create a new iterator with the given parameters
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// let it = ExponentialGenerator::new(0.5, 17., 3, 22, 2.);
+    /// let mut values: Vec<_> = it.map(|f| f.into_inner()).collect();
+    /// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(values[1], 17.);
+    /// ```
+    pub fn new(
+        quantile: f64,
+        value: f64,
+        num: usize,
+        seed: u64,
+        lambda: f64,
+    ) -> ExponentialGenerator {
+        assert!(num > 0);
+        assert!(lambda > 0.);
+        let rank = quantile_to_rank(quantile, num as u64) as usize;
+        let lesser_ratio = (rank - 1) as f64 / num as f64;
+        let distribution = ExponentialDistribution {
+            lambda,
+            lesser_ratio,
+            // Inverse of the cumulative distribution function
+            lesser_span: -(1. - lesser_ratio).ln() / lambda,
+        };
+        PinnedGenerator::with_distribution(quantile, value, num, seed, distribution)
+    }
+}
+
+impl PinnedDistribution for ExponentialDistribution {
+    /// Draw from the distribution, restricted to the values smaller than `value`
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        // Inverse transform sampling, with the random input restricted to [0, lesser_ratio)
+        let r = rng.gen::<f64>() * self.lesser_ratio;
+        let offset = -(1. - r).ln() / self.lambda;
+        value - self.lesser_span + offset
+    }
+
+    /// Draw from the distribution, restricted to the values greater or equal to `value`
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        // The distribution is memoryless, so its tail is the distribution itself, shifted.
+        // `1 - r` is in (0, 1], so that the logarithm is finite
+        let offset = -(1. - rng.gen::<f64>()).ln() / self.lambda;
+        value + offset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ordered_float::NotNan;
+
+    #[test]
+    fn distribution_shape() {
+        for &quantile in &[0., 0.5, 0.99] {
+            for &lambda in &[0.1, 1., 5.] {
+                let num = 10_000;
+                let values: Vec<_> = ExponentialGenerator::new(quantile, 17., num, 17, lambda)
+                    .map(NotNan::into_inner)
+                    .collect();
+
+                // Once shifted to start at zero, the mean of an exponential distribution is
+                // `1 / lambda`
+                let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+                let mean = values.iter().map(|v| v - min).sum::<f64>() / num as f64;
+                let relative_error = (mean * lambda - 1.).abs();
+                assert!(
+                    relative_error < 0.05,
+                    "quantile={}, lambda={}, mean={}",
+                    quantile,
+                    lambda,
+                    mean
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_value() {
+        // The span of the lesser values is lost when added to such a value
+        let num = 10;
+        let mut values: Vec<_> = ExponentialGenerator::new(0.5, 1e17, num, 1, 1.).collect();
+        assert_eq!(values.len(), num);
+
+        values.sort();
+        let rank = quantile_to_rank(0.5, num as u64) as usize;
+        assert!(values[rank - 2].into_inner() < 1e17);
+        assert_eq!(values[rank - 1].into_inner(), 1e17);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! This module provides iterators of `N` floats with the value `x` for the quantile `q`, where
 //! `N`, `x` and `q` can be directly controlled. The floats are represented by `NotNan<f64>`,
-//! because this type implements `Ord`.
+//! because this type implements `Ord`. `SequentialGenerator` can also produce other types, like
+//! `i64`.
 //!
 //! The rule respected is: `rank_x = ceil(q * (N - 1))`, where `rank_x` is defined as the number of
 //! values strictly smaller than `x`. At the extremes, with `q = 0`, `x` is the least returned value
@@ -10,24 +11,37 @@
 //!
 //! This module is mainly used to provide test data in order to test the quantile implementations.
 
+mod bimodal;
+mod duplicate;
+mod exponential;
+mod normal;
+mod ordered_f64;
+mod pinned;
 mod random;
 mod sequential;
 
-use ordered_float::NotNan;
 use std::iter::FusedIterator;
 
-/// The main trait representing an iterator of floats
-pub trait QuantileGenerator:
-    Iterator<Item = NotNan<f64>> + ExactSizeIterator + FusedIterator
-{
+/// The main trait representing an iterator of values with a known quantile
+pub trait QuantileGenerator: ExactSizeIterator + FusedIterator {
+    /// Return the configured quantile and the rank of the target value in the sorted output, as
+    /// given by `quantile_to_rank(quantile, num)`
+    fn target(&self) -> (f64, usize);
 }
 
-pub use random::RandomGenerator;
-pub use sequential::{SequentialGenerator, SequentialOrder};
+pub use bimodal::{BimodalDistribution, BimodalGenerator};
+pub use duplicate::{DuplicateDistribution, DuplicateGenerator};
+pub use exponential::{ExponentialDistribution, ExponentialGenerator};
+pub use normal::{NormalDistribution, NormalGenerator};
+pub use ordered_f64::OrderedF64;
+pub use pinned::{PinnedDistribution, PinnedGenerator};
+pub use random::{RandomDistribution, RandomGenerator};
+pub use sequential::{SequenceValue, SequentialGenerator, SequentialOrder};
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use ordered_float::NotNan;
 
     use crate::quantile_to_rank;
 
@@ -42,8 +56,8 @@ mod test {
 
     #[test]
     fn other_quantiles() {
-        for quantile in vec![0., 0.1, 0.2, 0.75, 0.99, 1.] {
-            for num in vec![1, 2, 5, 10, 100, 1000, 1001] {
+        for &quantile in &[0., 0.1, 0.2, 0.75, 0.99, 1.] {
+            for &num in &[1, 2, 5, 10, 100, 1000, 1001] {
                 check_all(quantile, 17., num);
             }
         }
@@ -53,6 +67,18 @@ mod test {
         let it = RandomGenerator::new(quantile, value, num, 17);
         check_one(it, quantile, value, num);
 
+        let it = NormalGenerator::new(quantile, value, num, 17, 3.);
+        check_one(it, quantile, value, num);
+
+        let it = ExponentialGenerator::new(quantile, value, num, 17, 0.5);
+        check_one(it, quantile, value, num);
+
+        let it = DuplicateGenerator::new(quantile, value, num, 17, 5);
+        check_one(it, quantile, value, num);
+
+        let it = BimodalGenerator::new(quantile, value, num, 17, (0., 100.), 0.3);
+        check_one(it, quantile, value, num);
+
         let it = SequentialGenerator::new(quantile, value, num, SequentialOrder::Ascending);
         check_one(it, quantile, value, num);
 
@@ -60,15 +86,28 @@ mod test {
         check_one(it, quantile, value, num);
     }
 
-    fn check_one<G: QuantileGenerator>(gen: G, quantile: f64, value: f64, num: usize) {
+    fn check_one<G>(gen: G, quantile: f64, value: f64, num: usize)
+    where
+        G: QuantileGenerator<Item = NotNan<f64>>,
+    {
+        // The generator describes where the target value is pinned
+        let (target_quantile, rank) = gen.target();
+        assert_eq!(target_quantile, quantile);
+        assert_eq!(rank, quantile_to_rank(quantile, num as u64) as usize);
+
         // Collect iterator into a vector
         let mut values: Vec<_> = gen.collect();
 
         // Calculate observed quantile
         values.sort();
-        let rank: usize = quantile_to_rank(quantile, num as u64) as usize;
         let actual = values[rank - 1];
 
-        assert_eq!(value, actual.into_inner(), "Sorted values: {:?}", values);
+        assert_eq!(
+            value,
+            actual.into_inner(),
+            "rank={}, sorted values: {:?}",
+            rank,
+            values
+        );
     }
 }
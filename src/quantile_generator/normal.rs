@@ -0,0 +1,88 @@
+use super::{PinnedDistribution, PinnedGenerator};
+use rand::Rng;
+use rand_pcg::Pcg64;
+use std::f64::consts::PI;
+
+/// An iterator that will generate normally-distributed random values
+///
+/// The distance of each value to `x` is drawn from a normal distribution with the given standard
+/// deviation, and the values are returned in a random order. The side of `x` each value falls
+/// into is chosen so that `x` ends up at the desired quantile: with `q = 0.5` the values follow a
+/// normal distribution centered at `x`.
+pub type NormalGenerator = PinnedGenerator<NormalDistribution>;
+
+/// The values drawn by a `NormalGenerator`
+#[derive(Debug, Clone)]
+pub struct NormalDistribution {
+    stddev: f64,
+}
+
+impl NormalGenerator {
+    /// Create a new iterator with the given parameters
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// let it = NormalGenerator::new(0.5, 17., 3, 22, 2.);
+    /// let mut values: Vec<_> = it.map(|f| f.into_inner()).collect();
+    /// values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(values[1], 17.);
+    /// ```
+    pub fn new(quantile: f64, value: f64, num: usize, seed: u64, stddev: f64) -> NormalGenerator {
+        assert!(stddev > 0.);
+        let distribution = NormalDistribution { stddev };
+        PinnedGenerator::with_distribution(quantile, value, num, seed, distribution)
+    }
+}
+
+impl NormalDistribution {
+    /// Draw the absolute value of a normal deviate, using the Box-Muller transform
+    fn next_non_zero_deviation(&self, rng: &mut Pcg64) -> f64 {
+        loop {
+            // `1 - r` is in (0, 1], so that the logarithm is finite
+            let radius = (-2. * (1. - rng.gen::<f64>()).ln()).sqrt();
+            let angle = 2. * PI * rng.gen::<f64>();
+            let deviation = (radius * angle.cos() * self.stddev).abs();
+            if deviation != 0. {
+                return deviation;
+            }
+        }
+    }
+}
+
+impl PinnedDistribution for NormalDistribution {
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        value - self.next_non_zero_deviation(rng)
+    }
+
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        value + self.next_non_zero_deviation(rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ordered_float::NotNan;
+
+    #[test]
+    fn standard_deviation() {
+        for &stddev in &[0.1, 1., 50.] {
+            let num = 10_000;
+            let values: Vec<_> = NormalGenerator::new(0.5, 17., num, 17, stddev)
+                .map(NotNan::into_inner)
+                .collect();
+
+            let mean = values.iter().sum::<f64>() / num as f64;
+            let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / num as f64;
+            let relative_error = (variance.sqrt() - stddev).abs() / stddev;
+            assert!(relative_error < 0.05, "stddev={}, mean={}", stddev, mean);
+            assert!(
+                (mean - 17.).abs() < 0.05 * stddev,
+                "stddev={}, mean={}",
+                stddev,
+                mean
+            );
+        }
+    }
+}
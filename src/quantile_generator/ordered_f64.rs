@@ -0,0 +1,118 @@
+use ordered_float::{FloatIsNan, NotNan};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// A float that is never NaN, and thus implements `Ord` and can be fed into the summaries
+///
+/// It can be built from a plain `f64` with `TryFrom`, which rejects NaN. An infallible
+/// `From<f64>` is not provided, because it would conflict with this fallible conversion. As with
+/// `NotNan`, arithmetic operations panic if their result is NaN.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrderedF64(NotNan<f64>);
+
+impl OrderedF64 {
+    /// Create a new value, returning an error if it is NaN
+    pub fn new(value: f64) -> Result<Self, FloatIsNan> {
+        NotNan::new(value).map(OrderedF64)
+    }
+
+    /// Return the wrapped float
+    pub fn into_inner(self) -> f64 {
+        self.0.into_inner()
+    }
+}
+
+impl TryFrom<f64> for OrderedF64 {
+    type Error = FloatIsNan;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        OrderedF64::new(value)
+    }
+}
+
+impl From<OrderedF64> for f64 {
+    fn from(value: OrderedF64) -> Self {
+        value.into_inner()
+    }
+}
+
+impl From<NotNan<f64>> for OrderedF64 {
+    fn from(value: NotNan<f64>) -> Self {
+        OrderedF64(value)
+    }
+}
+
+impl From<OrderedF64> for NotNan<f64> {
+    fn from(value: OrderedF64) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for OrderedF64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Add for OrderedF64 {
+    type Output = OrderedF64;
+
+    fn add(self, other: OrderedF64) -> OrderedF64 {
+        OrderedF64(self.0 + other.0)
+    }
+}
+
+impl Add<f64> for OrderedF64 {
+    type Output = OrderedF64;
+
+    fn add(self, other: f64) -> OrderedF64 {
+        OrderedF64(self.0 + other)
+    }
+}
+
+impl Sub for OrderedF64 {
+    type Output = OrderedF64;
+
+    fn sub(self, other: OrderedF64) -> OrderedF64 {
+        OrderedF64(self.0 - other.0)
+    }
+}
+
+impl Sub<f64> for OrderedF64 {
+    type Output = OrderedF64;
+
+    fn sub(self, other: f64) -> OrderedF64 {
+        OrderedF64(self.0 - other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let value = OrderedF64::try_from(17.5).unwrap();
+        assert_eq!(value.into_inner(), 17.5);
+        assert_eq!(f64::from(value), 17.5);
+        assert_eq!(NotNan::from(value), NotNan::from(17.5));
+        assert_eq!(OrderedF64::from(NotNan::from(17.5)), value);
+        assert_eq!(value.to_string(), "17.5");
+
+        assert!(OrderedF64::try_from(f64::NAN).is_err());
+        assert!(OrderedF64::new(f64::NAN).is_err());
+        assert!(OrderedF64::try_from(f64::INFINITY).is_ok());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = OrderedF64::try_from(17.).unwrap();
+        let b = OrderedF64::try_from(2.5).unwrap();
+        assert_eq!((a + b).into_inner(), 19.5);
+        assert_eq!((a - b).into_inner(), 14.5);
+        assert_eq!((a + 1.).into_inner(), 18.);
+        assert_eq!((a - 1.).into_inner(), 16.);
+        assert!(b < a);
+    }
+}
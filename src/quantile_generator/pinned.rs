@@ -0,0 +1,173 @@
+use super::QuantileGenerator;
+use crate::quantile_to_rank;
+use ordered_float::NotNan;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+use std::iter::{ExactSizeIterator, FusedIterator};
+
+/// How a `PinnedGenerator` draws the values on each side of its target value
+pub trait PinnedDistribution {
+    /// Draw a value smaller than `value`. Greater or equal results are replaced by the greatest
+    /// float smaller than `value`
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64;
+
+    /// Draw a value greater or equal to `value`. Smaller results are replaced by `value`
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64;
+}
+
+/// An iterator that will generate values such that `x` is at the desired quantile
+///
+/// The values other than `x` are drawn from a `PinnedDistribution`, on the side of `x` that keeps
+/// it at the desired quantile, and all values are returned in a random order.
+#[derive(Clone)]
+pub struct PinnedGenerator<D> {
+    quantile: f64,
+    rank: usize,
+    remaining_lesser: usize,
+    remaining: usize, // excluding the target value
+    value: f64,
+    published_value: bool,
+    rng: Pcg64,
+    distribution: D,
+}
+
+impl<D: PinnedDistribution> PinnedGenerator<D> {
+    /// Create a new iterator with the given parameters, drawing the other values from
+    /// `distribution`
+    pub fn with_distribution(
+        quantile: f64,
+        value: f64,
+        num: usize,
+        seed: u64,
+        distribution: D,
+    ) -> PinnedGenerator<D> {
+        assert!(num > 0);
+        let rank = quantile_to_rank(quantile, num as u64) as usize;
+        PinnedGenerator {
+            quantile,
+            rank,
+            remaining_lesser: rank - 1,
+            remaining: num - 1,
+            value,
+            published_value: false,
+            rng: Pcg64::seed_from_u64(seed),
+            distribution,
+        }
+    }
+}
+
+impl<D> PinnedGenerator<D> {
+    fn next_random(&mut self) -> f64 {
+        self.rng.gen()
+    }
+}
+
+impl<D: PinnedDistribution> Iterator for PinnedGenerator<D> {
+    type Item = NotNan<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // At each step, we'll select whether to generate a greater, lesser or the target value
+        // This decision is random, however with weights proportional to the number
+        // of remaining draws
+
+        // Check end of cursor
+        if self.remaining == 0 && self.published_value {
+            return None;
+        }
+
+        // Publish target value
+        if !self.published_value {
+            let remaining_ratio = 1. / (self.remaining + 1) as f64;
+            if self.next_random() < remaining_ratio {
+                self.published_value = true;
+                return Some(NotNan::from(self.value));
+            }
+        }
+
+        // Publish other values
+        let ratio = self.remaining_lesser as f64 / self.remaining as f64;
+        self.remaining -= 1;
+        if self.next_random() >= ratio {
+            // Greater or equal
+            let greater = self.distribution.next_greater(self.value, &mut self.rng);
+            Some(NotNan::from(greater.max(self.value)))
+        } else {
+            // Lesser. Rounding can reach the target value itself, in particular when it is large
+            // compared to the distances drawn by the distribution
+            self.remaining_lesser -= 1;
+            let lesser = self.distribution.next_lesser(self.value, &mut self.rng);
+            Some(NotNan::from(lesser.min(prev_float(self.value))))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut size = self.remaining;
+        if !self.published_value {
+            size += 1;
+        }
+        (size, Some(size))
+    }
+}
+
+impl<D: PinnedDistribution> FusedIterator for PinnedGenerator<D> {}
+
+impl<D: PinnedDistribution> ExactSizeIterator for PinnedGenerator<D> {}
+
+impl<D: PinnedDistribution> QuantileGenerator for PinnedGenerator<D> {
+    fn target(&self) -> (f64, usize) {
+        (self.quantile, self.rank)
+    }
+}
+
+/// Return the greatest float that is smaller than the finite `x`
+fn prev_float(x: f64) -> f64 {
+    if x == 0. {
+        // The negative float closest to zero
+        -f64::from_bits(1)
+    } else if x > 0. {
+        f64::from_bits(x.to_bits() - 1)
+    } else {
+        f64::from_bits(x.to_bits() + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A distribution whose distances are lost to rounding far from zero
+    #[derive(Clone)]
+    struct Tiny;
+
+    impl PinnedDistribution for Tiny {
+        fn next_lesser(&mut self, value: f64, _rng: &mut Pcg64) -> f64 {
+            value - 1e-3
+        }
+
+        fn next_greater(&mut self, value: f64, _rng: &mut Pcg64) -> f64 {
+            value - 1e-3
+        }
+    }
+
+    #[test]
+    fn sides_are_clamped() {
+        for &value in &[1e17, 17., 0., -1e17] {
+            let num = 101;
+            let mut values: Vec<_> = PinnedGenerator::with_distribution(0.5, value, num, 17, Tiny)
+                .map(NotNan::into_inner)
+                .collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert!(values[..50].iter().all(|&v| v < value));
+            assert!(values[50..].iter().all(|&v| v == value));
+        }
+    }
+
+    #[test]
+    fn prev_float_is_adjacent() {
+        for &x in &[1e17, 17., 1., 0., -0., -1., -1e17] {
+            let prev = prev_float(x);
+            assert!(prev < x);
+            assert!(x - prev <= x.abs() * f64::EPSILON || x - prev == f64::from_bits(1));
+        }
+    }
+}
@@ -1,19 +1,17 @@
-use super::QuantileGenerator;
-use crate::quantile_to_rank;
-use ordered_float::NotNan;
-use rand::{Rng, SeedableRng};
+use super::{PinnedDistribution, PinnedGenerator};
+use rand::Rng;
 use rand_pcg::Pcg64;
-use std::iter::{ExactSizeIterator, FusedIterator};
 
 /// An iterator that will generate random values
 ///
-/// The values are drawn randomly from the range `(x-1, x+1)` and returned in a random order.
-pub struct RandomGenerator {
-    remaining_lesser: usize,
-    remaining: usize, // excluding the target value
-    value: f64,
-    published_value: bool,
-    rng: Pcg64,
+/// The values are drawn randomly from the range `(x-spread, x+spread)` and returned in a random
+/// order. By default, `spread = 1`.
+pub type RandomGenerator = PinnedGenerator<RandomDistribution>;
+
+/// The values drawn by a `RandomGenerator`
+#[derive(Debug, Clone)]
+pub struct RandomDistribution {
+    spread: f64,
 }
 
 impl RandomGenerator {
@@ -28,83 +26,51 @@ impl RandomGenerator {
     /// assert_eq!(values, vec![16.520451506320533, 17.352059635936964, 17.0]);
     /// ```
     pub fn new(quantile: f64, value: f64, num: usize, seed: u64) -> RandomGenerator {
-        assert!(num > 0);
-        let remaining_lesser = quantile_to_rank(quantile, num as u64) as usize - 1;
-        RandomGenerator {
-            remaining_lesser,
-            remaining: num - 1,
-            value,
-            published_value: false,
-            rng: Pcg64::seed_from_u64(seed),
-        }
-    }
-}
-
-impl RandomGenerator {
-    fn next_random(&mut self) -> f64 {
-        self.rng.gen()
+        RandomGenerator::with_spread(quantile, value, num, seed, 1.)
     }
 
-    fn next_non_zero_random(&mut self) -> f64 {
-        let mut r = self.next_random();
-        while r == 0. {
-            r = self.next_random();
-        }
-        r
+    /// Create a new iterator whose values other than `value` fall in `(value-spread, value+spread)`
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// let it = RandomGenerator::with_spread(0.5, 17., 3, 22, 1000.);
+    /// let values: Vec<_> = it.map(|f| f.into_inner()).collect();
+    /// assert!(values.iter().all(|&v| v > 17. - 1000. && v < 17. + 1000.));
+    /// ```
+    pub fn with_spread(
+        quantile: f64,
+        value: f64,
+        num: usize,
+        seed: u64,
+        spread: f64,
+    ) -> RandomGenerator {
+        assert!(spread > 0.);
+        let distribution = RandomDistribution { spread };
+        PinnedGenerator::with_distribution(quantile, value, num, seed, distribution)
     }
 }
 
-impl Iterator for RandomGenerator {
-    type Item = NotNan<f64>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // At each step, we'll select whether to generate a greater, lesser or the target value
-        // This decision is random, however with weights proportional to the number
-        // of remaining draws
-
-        // Check end of cursor
-        if self.remaining == 0 && self.published_value {
-            return None;
-        }
-
-        // Publish target value
-        if !self.published_value {
-            let remaining_ratio = 1. / (self.remaining + 1) as f64;
-            if self.next_random() < remaining_ratio {
-                self.published_value = true;
-                return Some(NotNan::from(self.value));
-            }
-        }
-
-        // Publish other values
-        let ratio = self.remaining_lesser as f64 / self.remaining as f64;
-        self.remaining -= 1;
-        if self.next_random() >= ratio {
-            // Greater or equal
-            Some(NotNan::from(self.value + self.next_random()))
-        } else {
-            // Lesser
-            self.remaining_lesser -= 1;
-            Some(NotNan::from(self.value - self.next_non_zero_random()))
+impl PinnedDistribution for RandomDistribution {
+    fn next_lesser(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        // Zero would give `value` itself
+        let mut r: f64 = rng.gen();
+        while r == 0. {
+            r = rng.gen();
         }
+        value - r * self.spread
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let mut size = self.remaining;
-        if !self.published_value {
-            size += 1;
-        }
-        return (size, Some(size));
+    fn next_greater(&mut self, value: f64, rng: &mut Pcg64) -> f64 {
+        value + rng.gen::<f64>() * self.spread
     }
 }
 
-impl FusedIterator for RandomGenerator {}
-
-impl ExactSizeIterator for RandomGenerator {}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::quantile_to_rank;
+    use ordered_float::NotNan;
 
     #[test]
     fn respect_seed() {
@@ -141,6 +107,33 @@ mod test {
             ],
         );
     }
-}
 
-impl QuantileGenerator for RandomGenerator {}
+    #[test]
+    fn clone_mid_iteration() {
+        let mut it = RandomGenerator::new(0.5, 17., 100, 17);
+        it.by_ref().take(40).for_each(drop);
+
+        let cloned = it.clone();
+        assert_eq!(cloned.len(), 60);
+        assert_eq!(it.collect::<Vec<_>>(), cloned.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn large_spread() {
+        for &quantile in &[0., 0.5, 0.9, 1.] {
+            let num = 1000;
+            let mut values: Vec<_> =
+                RandomGenerator::with_spread(quantile, 17., num, 17, 1000.).collect();
+            values.sort();
+
+            let rank = quantile_to_rank(quantile, num as u64) as usize;
+            assert_eq!(values[rank - 1].into_inner(), 17.);
+
+            // Values are spread over the whole range
+            let first = values[0].into_inner();
+            let last = values[num - 1].into_inner();
+            assert!(first > 17. - 1000. && last < 17. + 1000.);
+            assert!(last - first > 100.);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-//! An iterator over an ordered sequence of floats
+//! An iterator over an ordered sequence of values
 
 use ordered_float::NotNan;
 use crate::quantile_to_rank;
@@ -6,25 +6,51 @@ use std::iter::{ExactSizeIterator, FusedIterator};
 use super::QuantileGenerator;
 
 /// An iterator that will generate sequential values
-pub struct SequentialGenerator {
+///
+/// By default, the values are floats, but any type implementing `SequenceValue` can be used.
+#[derive(Clone)]
+pub struct SequentialGenerator<T = NotNan<f64>> {
+    quantile: f64,
+    rank: usize,
     // `value` could be simply added to `offset`, but we keep them separate to
     // avoid float imprecision and make sure the actual value is returned at the
     // right position
-    value: f64,
+    value: T,
     position: usize,
-    direction: f64,
-    offset: f64,
+    // Number of values already returned from the back
+    back: usize,
+    direction: i64,
+    offset: i64,
     num: usize,
 }
 
+/// A type of value that can be generated by `SequentialGenerator`
+pub trait SequenceValue: Copy {
+    /// Return the value at the given signed distance from this one, in units of one
+    fn at_distance(self, distance: i64) -> Self;
+}
+
+impl SequenceValue for NotNan<f64> {
+    fn at_distance(self, distance: i64) -> Self {
+        NotNan::from(self.into_inner() + distance as f64)
+    }
+}
+
+impl SequenceValue for i64 {
+    fn at_distance(self, distance: i64) -> Self {
+        self + distance
+    }
+}
+
 /// The order in which to return the values
+#[derive(Clone, Copy)]
 pub enum SequentialOrder {
     Ascending,
     Descending,
 }
 
 impl SequentialGenerator {
-    /// Create a new iterator with the given parameters
+    /// Create a new iterator of floats with the given parameters
     ///
     /// # Example
     /// ```
@@ -40,15 +66,38 @@ impl SequentialGenerator {
         num: usize,
         order: SequentialOrder,
     ) -> SequentialGenerator {
+        SequentialGenerator::with_target(quantile, NotNan::from(value), num, order)
+    }
+}
+
+impl<T: SequenceValue> SequentialGenerator<T> {
+    /// Create a new iterator of any supported type with the given parameters
+    ///
+    /// # Example
+    /// ```
+    /// use fast_quantiles::quantile_generator::*;
+    /// let it = SequentialGenerator::with_target(0.5, 17i64, 3, SequentialOrder::Descending);
+    /// let values: Vec<_> = it.collect();
+    /// assert_eq!(values, vec![18, 17, 16]);
+    /// ```
+    pub fn with_target(
+        quantile: f64,
+        value: T,
+        num: usize,
+        order: SequentialOrder,
+    ) -> SequentialGenerator<T> {
         assert!(num > 0);
         let rank = quantile_to_rank(quantile, num as u64) as usize;
         let (direction, offset) = match order {
-            SequentialOrder::Ascending => (1., -(rank as f64) + 1.),
-            _ => (-1., (num - rank) as f64),
+            SequentialOrder::Ascending => (1, -(rank as i64) + 1),
+            _ => (-1, (num - rank) as i64),
         };
         SequentialGenerator {
+            quantile,
+            rank,
             value,
             position: 0,
+            back: 0,
             direction,
             offset,
             num,
@@ -56,29 +105,106 @@ impl SequentialGenerator {
     }
 }
 
-impl Iterator for SequentialGenerator {
-    type Item = NotNan<f64>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<T: SequenceValue> SequentialGenerator<T> {
+    fn term(&self, i: usize) -> T {
         // The terms of the sequence are defined as:
         // v[i] = value + alpha*i + beta
-        if self.position == self.num {
+        self.value
+            .at_distance(self.direction * i as i64 + self.offset)
+    }
+}
+
+impl<T: SequenceValue> Iterator for SequentialGenerator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position + self.back == self.num {
             None
         } else {
-            let r = self.value + (self.direction * self.position as f64 + self.offset);
+            let r = self.term(self.position);
             self.position += 1;
-            Some(NotNan::from(r))
+            Some(r)
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.num - self.position;
+        let size = self.num - self.position - self.back;
         (size, Some(size))
     }
 }
 
-impl FusedIterator for SequentialGenerator {}
+impl<T: SequenceValue> DoubleEndedIterator for SequentialGenerator<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.position + self.back == self.num {
+            None
+        } else {
+            let r = self.term(self.num - 1 - self.back);
+            self.back += 1;
+            Some(r)
+        }
+    }
+}
+
+impl<T: SequenceValue> FusedIterator for SequentialGenerator<T> {}
 
-impl ExactSizeIterator for SequentialGenerator {}
+impl<T: SequenceValue> ExactSizeIterator for SequentialGenerator<T> {}
+
+impl<T: SequenceValue> QuantileGenerator for SequentialGenerator<T> {
+    fn target(&self) -> (f64, usize) {
+        (self.quantile, self.rank)
+    }
+}
 
-impl QuantileGenerator for SequentialGenerator {}
\ No newline at end of file
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Summary;
+
+    #[test]
+    fn double_ended() {
+        for &order in &[SequentialOrder::Ascending, SequentialOrder::Descending] {
+            let it = SequentialGenerator::new(0.3, 17., 10, order);
+            let mut forward: Vec<_> = it.clone().collect();
+            forward.reverse();
+            let backward: Vec<_> = it.rev().collect();
+            assert_eq!(forward, backward);
+        }
+    }
+
+    #[test]
+    fn mixed_ends() {
+        let mut it = SequentialGenerator::new(0.5, 17., 5, SequentialOrder::Ascending);
+        assert_eq!(it.len(), 5);
+        assert_eq!(it.next(), Some(NotNan::from(15.)));
+        assert_eq!(it.len(), 4);
+        assert_eq!(it.next_back(), Some(NotNan::from(19.)));
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.next_back(), Some(NotNan::from(18.)));
+        assert_eq!(it.next(), Some(NotNan::from(16.)));
+        assert_eq!(it.len(), 1);
+        assert_eq!(it.next(), Some(NotNan::from(17.)));
+        assert_eq!(it.len(), 0);
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+        assert_eq!(it.len(), 0);
+    }
+
+    #[test]
+    fn integer_values() {
+        let num = 1000;
+        let it = SequentialGenerator::with_target(0.3, 17i64, num, SequentialOrder::Ascending);
+        let (quantile, rank) = it.target();
+
+        let mut summary = Summary::new(0.01);
+        let mut values = Vec::new();
+        for value in it {
+            summary.insert_one(value);
+            values.push(value);
+        }
+        assert_eq!(values[rank - 1], 17);
+
+        // Consecutive integers: the rank error is the distance to the target value
+        let queried = *summary.query(quantile).unwrap();
+        assert!((queried - 17).abs() as f64 <= 0.01 * num as f64);
+    }
+}
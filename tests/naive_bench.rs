@@ -0,0 +1,26 @@
+//! The benchmarks compare every algorithm against the naive baseline, so check it separately
+
+use fast_quantiles::quantile_generator::RandomGenerator;
+
+#[path = "../benches/common/naive.rs"]
+mod naive_query;
+
+#[test]
+fn naive_query_finds_pinned_value() {
+    assert_eq!(naive_query::query::<u64>(&mut [], 0.5), None);
+
+    // The generator pins the value 17 at the desired quantile, including at the maximum rank
+    for &num in &[1, 2, 1000, 1001] {
+        for &quantile in &[0., 0.25, 0.5, 0.9, 1.] {
+            let mut values: Vec<_> = RandomGenerator::new(quantile, 17., num, 17).collect();
+            let answer = naive_query::query(&mut values, quantile).unwrap();
+            assert_eq!(
+                answer.into_inner(),
+                17.,
+                "num={}, quantile={}",
+                num,
+                quantile
+            );
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Use the crate only through its public exports, as an external consumer would
+
+use fast_quantiles::{quantile_to_rank, rank_to_quantile, Summary};
+
+#[test]
+fn rank_round_trip() {
+    // Powers of two keep `rank / num * num` exact in floating point
+    for &num in &[1, 2, 4, 16, 1024] {
+        for rank in 1..=num {
+            let quantile = rank_to_quantile(rank, num);
+            assert!((0. ..=1.).contains(&quantile));
+            assert_eq!(quantile_to_rank(quantile, num), rank);
+        }
+    }
+}
+
+#[test]
+fn summary_insert_and_query() {
+    // Also run by `cargo test --no-default-features`, against the `no_std` build of the crate
+    let mut summary = Summary::new(0.01);
+    for i in 0..1000 {
+        summary.insert_one(i * 7919 % 1000);
+    }
+    assert_eq!(summary.len(), 1000);
+    assert_eq!(summary.query(0.), Some(&0));
+    assert_eq!(summary.query(1.), Some(&999));
+    let median = *summary.query(0.5).unwrap();
+    assert!((490..=510).contains(&median), "{}", median);
+}
+
+#[cfg(feature = "quantile-generator")]
+#[test]
+fn generators_pin_the_target_value() {
+    use fast_quantiles::quantile_generator::*;
+    use ordered_float::NotNan;
+
+    fn check<G: QuantileGenerator<Item = NotNan<f64>>>(gen: G, quantile: f64, num: usize) {
+        assert_eq!(gen.len(), num);
+        let mut values: Vec<_> = gen.collect();
+        values.sort();
+        let rank = quantile_to_rank(quantile, num as u64) as usize;
+        assert_eq!(values[rank - 1].into_inner(), 17.);
+    }
+
+    for &quantile in &[0., 0.5, 0.9, 1.] {
+        check(
+            RandomGenerator::new(quantile, 17., 1000, 17),
+            quantile,
+            1000,
+        );
+        check(
+            SequentialGenerator::new(quantile, 17., 1000, SequentialOrder::Ascending),
+            quantile,
+            1000,
+        );
+        check(
+            SequentialGenerator::new(quantile, 17., 1000, SequentialOrder::Descending),
+            quantile,
+            1000,
+        );
+    }
+}
+
+/// Feed the values into a summary and check that querying every rank respects the maximum error
+#[cfg(feature = "quantile-generator")]
+fn check_max_error(values: &[ordered_float::NotNan<f64>], epsilon: f64) {
+    let mut summary = Summary::new(epsilon);
+    for &value in values {
+        summary.insert_one(value);
+    }
+    check_summary_error(&summary, values, epsilon);
+}
+
+/// Check that querying every rank of a summary of the values respects the maximum error
+#[cfg(feature = "quantile-generator")]
+fn check_summary_error(
+    summary: &Summary<ordered_float::NotNan<f64>>,
+    values: &[ordered_float::NotNan<f64>],
+    epsilon: f64,
+) {
+    let mut sorted_values = values.to_vec();
+    sorted_values.sort();
+    let num = values.len() as u64;
+    for rank in 1..=num {
+        let quantile = rank_to_quantile(rank, num);
+        let target_rank = quantile_to_rank(quantile, num);
+        let queried = summary.query(quantile).unwrap();
+
+        // With ties, any rank held by a copy of the queried value is a valid answer
+        let first_rank = sorted_values.partition_point(|v| v < queried) as u64 + 1;
+        let last_rank = sorted_values.partition_point(|v| v <= queried) as u64;
+        let error = if target_rank < first_rank {
+            first_rank - target_rank
+        } else {
+            target_rank.saturating_sub(last_rank)
+        };
+        assert!(
+            error as f64 / num as f64 <= epsilon,
+            "target_rank={}, got_ranks={}..={}",
+            target_rank,
+            first_rank,
+            last_rank
+        );
+    }
+}
+
+#[cfg(feature = "quantile-generator")]
+#[test]
+fn duplicated_values_respect_max_error() {
+    use fast_quantiles::quantile_generator::DuplicateGenerator;
+
+    // 10k values from only 5 distinct levels, in random and sorted order
+    let mut values: Vec<_> = DuplicateGenerator::new(0.5, 17., 10_000, 17, 5).collect();
+    for &epsilon in &[0.1, 0.01] {
+        check_max_error(&values, epsilon);
+    }
+    values.sort();
+    for &epsilon in &[0.1, 0.01] {
+        check_max_error(&values, epsilon);
+    }
+}
+
+#[cfg(feature = "quantile-generator")]
+#[test]
+fn bimodal_values_respect_max_error() {
+    use fast_quantiles::quantile_generator::BimodalGenerator;
+
+    // Two far apart modes, with the target value in the gap or inside one of them
+    for &(quantile, value) in &[(0.3, 50.), (0.5, 50.), (0.65, 100.), (0.9, 100.)] {
+        let values: Vec<_> =
+            BimodalGenerator::new(quantile, value, 10_000, 17, (0., 100.), 0.3).collect();
+        check_max_error(&values, 0.05);
+    }
+}
+
+#[cfg(feature = "quantile-generator")]
+#[test]
+fn merged_summaries_respect_max_error() {
+    use fast_quantiles::quantile_generator::RandomGenerator;
+
+    // Split a seeded stream across two summaries, as parallel workers would
+    let values: Vec<_> = RandomGenerator::new(0.5, 17., 10_000, 17).collect();
+    for &epsilon in &[0.1, 0.01] {
+        let mut summary = Summary::new(epsilon);
+        let mut other = Summary::new(epsilon);
+        for (i, &value) in values.iter().enumerate() {
+            if i % 2 == 0 {
+                summary.insert_one(value);
+            } else {
+                other.insert_one(value);
+            }
+        }
+
+        summary.merge(other);
+        assert_eq!(summary.len(), values.len() as u64);
+        check_summary_error(&summary, &values, epsilon);
+    }
+}